@@ -0,0 +1,6 @@
+-- Receipt details recorded once a sent transaction is mined
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS block_number BIGINT;
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS gas_used BIGINT;
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS effective_gas_price BIGINT;
+
+CREATE INDEX IF NOT EXISTS idx_processed_jobs_status ON processed_jobs (status);
@@ -0,0 +1,13 @@
+-- Block hash of the inclusion block, used to detect reorganisations
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS block_hash TEXT;
+
+-- Audit trail of notable state changes per job
+CREATE TABLE IF NOT EXISTS job_events (
+    id BIGSERIAL PRIMARY KEY,
+    record_id BIGINT NOT NULL,
+    event_type TEXT NOT NULL,
+    details JSONB NOT NULL DEFAULT '{}'::jsonb,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_events_record_id ON job_events (record_id);
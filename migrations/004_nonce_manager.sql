@@ -0,0 +1,19 @@
+-- Next nonce to hand out per signer address
+CREATE TABLE IF NOT EXISTS signer_nonces (
+    address TEXT PRIMARY KEY,
+    next_nonce BIGINT NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Every nonce handed out, so failed broadcasts can be detected and filled
+CREATE TABLE IF NOT EXISTS nonce_reservations (
+    address TEXT NOT NULL,
+    nonce BIGINT NOT NULL,
+    status TEXT NOT NULL CHECK (status IN ('reserved', 'broadcast', 'released', 'filled')),
+    tx_hash TEXT,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    PRIMARY KEY (address, nonce)
+);
+
+CREATE INDEX IF NOT EXISTS idx_nonce_reservations_status ON nonce_reservations (address, status);
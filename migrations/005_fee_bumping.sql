@@ -0,0 +1,15 @@
+-- Nonce and last broadcast time of the job's current transaction
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS nonce BIGINT;
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS sent_at TIMESTAMP WITH TIME ZONE;
+
+-- Every transaction broadcast for a job, including fee-bumped replacements
+CREATE TABLE IF NOT EXISTS job_transactions (
+    tx_hash TEXT PRIMARY KEY,
+    record_id BIGINT NOT NULL,
+    nonce BIGINT NOT NULL,
+    max_fee_per_gas BIGINT NOT NULL,
+    max_priority_fee_per_gas BIGINT NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+CREATE INDEX IF NOT EXISTS idx_job_transactions_record_id ON job_transactions (record_id);
@@ -0,0 +1,4 @@
+-- Allow operators to cancel jobs
+ALTER TABLE processed_jobs DROP CONSTRAINT IF EXISTS processed_jobs_status_check;
+ALTER TABLE processed_jobs ADD CONSTRAINT processed_jobs_status_check
+    CHECK (status IN ('pending', 'sent', 'confirmed', 'failed', 'cancelled'));
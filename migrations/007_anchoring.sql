@@ -0,0 +1,22 @@
+-- Merkle roots published in anchoring mode
+CREATE TABLE IF NOT EXISTS anchor_batches (
+    id BIGSERIAL PRIMARY KEY,
+    merkle_root TEXT NOT NULL,
+    tx_hash TEXT NOT NULL,
+    leaf_count INTEGER NOT NULL,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- Per-record Merkle proofs, so every record can be verified on its own
+CREATE TABLE IF NOT EXISTS anchor_proofs (
+    record_id BIGINT PRIMARY KEY,
+    batch_id BIGINT NOT NULL REFERENCES anchor_batches (id),
+    leaf_index INTEGER NOT NULL,
+    leaf_hash TEXT NOT NULL,
+    proof JSONB NOT NULL
+);
+
+-- One anchoring transaction covers many jobs
+ALTER TABLE job_transactions DROP CONSTRAINT IF EXISTS job_transactions_pkey;
+ALTER TABLE job_transactions ADD PRIMARY KEY (record_id, tx_hash);
+CREATE INDEX IF NOT EXISTS idx_job_transactions_tx_hash ON job_transactions (tx_hash);
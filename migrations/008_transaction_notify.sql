@@ -0,0 +1,12 @@
+-- Wake listening workers as soon as a transaction is inserted
+CREATE OR REPLACE FUNCTION notify_new_transaction() RETURNS trigger AS $$
+BEGIN
+    PERFORM pg_notify('new_transactions', NEW.id::text);
+    RETURN NEW;
+END;
+$$ LANGUAGE plpgsql;
+
+DROP TRIGGER IF EXISTS transactions_notify_insert ON transactions;
+CREATE TRIGGER transactions_notify_insert
+    AFTER INSERT ON transactions
+    FOR EACH ROW EXECUTE FUNCTION notify_new_transaction();
@@ -0,0 +1,6 @@
+-- Durable per-worker position in the transactions table
+CREATE TABLE IF NOT EXISTS worker_cursors (
+    worker_name TEXT PRIMARY KEY,
+    last_id BIGINT NOT NULL DEFAULT 0,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
@@ -0,0 +1,9 @@
+-- Lease columns so several replicas can claim disjoint batches of transactions
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS lease_owner TEXT;
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS lease_expires_at TIMESTAMP WITH TIME ZONE;
+
+-- Rows handed off before leasing existed are done
+UPDATE transactions SET status = 'processed'
+WHERE status = 'pending' AND id IN (SELECT record_id FROM processed_jobs);
+
+CREATE INDEX IF NOT EXISTS idx_transactions_pending ON transactions (id) WHERE status = 'pending';
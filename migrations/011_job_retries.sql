@@ -0,0 +1,7 @@
+-- Attempt counting and backoff scheduling for failed jobs
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS attempts INTEGER NOT NULL DEFAULT 0;
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS last_error TEXT;
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS next_attempt_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_processed_jobs_next_attempt
+    ON processed_jobs (next_attempt_at) WHERE status = 'failed';
@@ -0,0 +1,18 @@
+-- Jobs that ran out of retries or can never succeed
+ALTER TABLE processed_jobs DROP CONSTRAINT IF EXISTS processed_jobs_status_check;
+ALTER TABLE processed_jobs ADD CONSTRAINT processed_jobs_status_check
+    CHECK (status IN ('pending', 'sent', 'confirmed', 'failed', 'cancelled', 'dead_lettered'));
+
+CREATE TABLE IF NOT EXISTS dead_letters (
+    id BIGSERIAL PRIMARY KEY,
+    record_id BIGINT NOT NULL,
+    attempts INTEGER NOT NULL,
+    -- Every recorded failure of the job, oldest first
+    errors JSONB NOT NULL DEFAULT '[]'::jsonb,
+    -- The transactions row as it was when the job was dead-lettered
+    payload JSONB,
+    created_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP,
+    replayed_at TIMESTAMP WITH TIME ZONE
+);
+
+CREATE INDEX IF NOT EXISTS idx_dead_letters_record_id ON dead_letters (record_id);
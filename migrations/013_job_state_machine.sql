@@ -0,0 +1,10 @@
+-- Explicit job states: claimed -> validated -> signed -> broadcast -> confirmed/failed
+ALTER TABLE processed_jobs DROP CONSTRAINT IF EXISTS processed_jobs_status_check;
+
+UPDATE processed_jobs SET status = 'claimed' WHERE status = 'pending';
+UPDATE processed_jobs SET status = 'broadcast' WHERE status = 'sent';
+
+ALTER TABLE processed_jobs ADD CONSTRAINT processed_jobs_status_check
+    CHECK (status IN (
+        'claimed', 'validated', 'signed', 'broadcast', 'confirmed', 'failed', 'cancelled', 'dead_lettered'
+    ));
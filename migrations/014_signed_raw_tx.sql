@@ -0,0 +1,9 @@
+-- Signed transaction written ahead of the broadcast, so it can be rebroadcast byte for byte
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS raw_tx BYTEA;
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS max_fee_per_gas BIGINT;
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS max_priority_fee_per_gas BIGINT;
+
+-- Signed reservations survive a nonce resync until their transaction is broadcast
+ALTER TABLE nonce_reservations DROP CONSTRAINT IF EXISTS nonce_reservations_status_check;
+ALTER TABLE nonce_reservations ADD CONSTRAINT nonce_reservations_status_check
+    CHECK (status IN ('reserved', 'signed', 'broadcast', 'released', 'filled'));
@@ -0,0 +1,39 @@
+-- Typed statuses for transactions and jobs
+DO $$
+BEGIN
+    CREATE TYPE transaction_status AS ENUM ('pending', 'processed');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+DO $$
+BEGIN
+    CREATE TYPE job_status AS ENUM (
+        'claimed', 'validated', 'signed', 'broadcast', 'confirmed', 'failed', 'cancelled', 'dead_lettered'
+    );
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+-- Partial indexes on status are rebuilt below, since their text predicates cannot follow the type change
+DO $$
+BEGIN
+    IF (SELECT data_type FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = 'transactions' AND column_name = 'status') <> 'USER-DEFINED' THEN
+        DROP INDEX IF EXISTS idx_transactions_pending;
+        UPDATE transactions SET status = 'pending' WHERE status IS NULL;
+        ALTER TABLE transactions ALTER COLUMN status DROP DEFAULT;
+        ALTER TABLE transactions ALTER COLUMN status TYPE transaction_status USING status::transaction_status;
+        ALTER TABLE transactions ALTER COLUMN status SET DEFAULT 'pending', ALTER COLUMN status SET NOT NULL;
+    END IF;
+
+    IF (SELECT data_type FROM information_schema.columns
+        WHERE table_schema = current_schema() AND table_name = 'processed_jobs' AND column_name = 'status') <> 'USER-DEFINED' THEN
+        DROP INDEX IF EXISTS idx_processed_jobs_next_attempt;
+        ALTER TABLE processed_jobs DROP CONSTRAINT IF EXISTS processed_jobs_status_check;
+        ALTER TABLE processed_jobs ALTER COLUMN status TYPE job_status USING status::job_status;
+        ALTER TABLE processed_jobs ALTER COLUMN status SET NOT NULL;
+    END IF;
+END $$;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_pending ON transactions (id) WHERE status = 'pending';
+CREATE INDEX IF NOT EXISTS idx_processed_jobs_next_attempt
+    ON processed_jobs (next_attempt_at) WHERE status = 'failed';
@@ -0,0 +1,5 @@
+-- Rows that must not be sent before a given time
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS execute_after TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_transactions_scheduled
+    ON transactions (execute_after) WHERE status = 'pending' AND execute_after IS NOT NULL;
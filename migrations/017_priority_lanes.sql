@@ -0,0 +1,10 @@
+-- Urgency lanes: higher lanes are claimed first and may pay a higher priority fee
+DO $$
+BEGIN
+    CREATE TYPE transaction_priority AS ENUM ('routine', 'high', 'urgent');
+EXCEPTION WHEN duplicate_object THEN NULL;
+END $$;
+
+ALTER TABLE transactions ADD COLUMN IF NOT EXISTS priority transaction_priority NOT NULL DEFAULT 'routine';
+
+CREATE INDEX IF NOT EXISTS idx_transactions_pending_priority ON transactions (priority, id) WHERE status = 'pending';
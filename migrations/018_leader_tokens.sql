@@ -0,0 +1,14 @@
+-- Fencing tokens of the current leader of every singleton worker role
+CREATE TABLE IF NOT EXISTS leader_tokens (
+    role TEXT PRIMARY KEY,
+    token BIGINT NOT NULL,
+    updated_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+);
+
+-- True while `p_token` is still the newest token of `p_role`, or when the write
+-- is not fenced at all. The row stays share-locked until the caller commits, so
+-- a successor cannot register in between the check and the write.
+CREATE OR REPLACE FUNCTION holds_leadership(p_role TEXT, p_token BIGINT) RETURNS BOOLEAN AS $$
+    SELECT p_role IS NULL
+        OR EXISTS (SELECT 1 FROM leader_tokens WHERE role = p_role AND token = p_token FOR SHARE);
+$$ LANGUAGE sql VOLATILE;
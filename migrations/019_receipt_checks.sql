@@ -0,0 +1,5 @@
+-- When the confirmation worker last looked for a receipt, so every sent job gets its turn
+ALTER TABLE processed_jobs ADD COLUMN IF NOT EXISTS checked_at TIMESTAMP WITH TIME ZONE;
+
+CREATE INDEX IF NOT EXISTS idx_processed_jobs_checked_at
+    ON processed_jobs (checked_at NULLS FIRST) WHERE status = 'broadcast';
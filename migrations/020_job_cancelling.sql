@@ -0,0 +1,2 @@
+-- Jobs whose cancellation was broadcast but is not mined yet; the original may still win
+ALTER TYPE job_status ADD VALUE IF NOT EXISTS 'cancelling' AFTER 'broadcast';
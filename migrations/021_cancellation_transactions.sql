@@ -0,0 +1,7 @@
+-- Which transactions of a job are zero-value cancellations, so a mined one cancels the job
+ALTER TABLE job_transactions ADD COLUMN IF NOT EXISTS cancellation BOOLEAN NOT NULL DEFAULT FALSE;
+
+-- Cancelling jobs are watched for receipts alongside broadcast ones
+DROP INDEX IF EXISTS idx_processed_jobs_checked_at;
+CREATE INDEX IF NOT EXISTS idx_processed_jobs_checked_at
+    ON processed_jobs (checked_at NULLS FIRST) WHERE status IN ('broadcast', 'cancelling');
@@ -0,0 +1,25 @@
+use crate::error::AppError;
+use axum::{
+    http::StatusCode,
+    response::{IntoResponse, Json, Response},
+};
+use tracing::error;
+
+impl IntoResponse for AppError {
+    fn into_response(self) -> Response {
+        let status = match &self {
+            AppError::Validation(_) | AppError::Serialization(_) => StatusCode::BAD_REQUEST,
+            AppError::NotFound(_) => StatusCode::NOT_FOUND,
+            AppError::Conflict(_) => StatusCode::CONFLICT,
+            AppError::Blockchain(_) => StatusCode::BAD_GATEWAY,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+
+        if status.is_server_error() {
+            error!("Request failed: {}", self);
+        }
+
+        let body = serde_json::json!({ "error": self.to_string() });
+        (status, Json(body)).into_response()
+    }
+}
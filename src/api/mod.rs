@@ -1 +1,2 @@
-pub mod routes; 
\ No newline at end of file
+pub mod error;
+pub mod routes;
\ No newline at end of file
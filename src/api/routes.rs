@@ -1,41 +1,81 @@
 use crate::{
-    application::worker::polling_worker::PollingWorker,
-    config::Config,
-    domain::services::transaction_processor::{PostgresTransactionRepository, TransactionProcessorService},
+    application::{
+        handlers::{dead_letters, jobs, records},
+        worker::{
+            anchoring_worker::AnchoringWorker, confirmation_worker::ConfirmationWorker,
+            fee_bump_worker::FeeBumpWorker, leader_elected_worker::LeaderElectedService, metrics::WorkerMetrics,
+            nonce_worker::NonceWorker,
+            polling_worker::PollingWorker, retry_worker::RetryWorker, stale_job_worker::StaleJobWorker,
+        },
+    },
+    config::{BlockchainMode, Config, FeeBumpConfig, WorkerMode},
+    domain::services::{
+        retry_policy::RetryPolicy,
+        transaction_processor::{PostgresTransactionRepository, TransactionProcessorService},
+    },
     error::AppResult,
-    infrastructure::{blockchain::client::BlockchainClient, database::repositories::processed_jobs_repo::ProcessedJobsTracker},
-    shared::traits::AppService
+    infrastructure::{
+        database::{
+            listener::PostgresTransactionListener,
+            repositories::{
+                anchor_repo::AnchorRepository, cursor_repo::CursorRepository,
+                leader_token_repo::LeaderTokenRepository, processed_jobs_repo::ProcessedJobsTracker,
+            },
+        },
+        redis::leader::LeaderElection,
+    },
+    shared::{
+        shutdown::cancel_on_signal,
+        traits::{
+            AnchorStore, AppService, BlockchainService, ProcessedJobsTracker as ProcessedJobsTrackerTrait,
+            TransactionRepository,
+        },
+    },
 };
 use axum::{
     extract::State,
     http::StatusCode,
     response::Json,
-    routing::get,
+    routing::{get, post},
     Router,
 };
 use redis::Client;
 use sqlx::PgPool;
-use std::sync::Arc;
-use tracing::info;
+use std::{sync::Arc, time::Duration};
+use tokio::{task::JoinHandle, time::Instant};
+use tokio_util::sync::CancellationToken;
+use tracing::{info, warn};
 
 #[derive(Clone)]
 pub struct AppState {
     pub db_pool: PgPool,
     pub redis_client: Client,
-    pub blockchain_client: BlockchainClient,
+    pub blockchain_client: Arc<dyn BlockchainService + Send + Sync>,
+    pub blockchain_mode: BlockchainMode,
+    pub transaction_repository: Arc<dyn TransactionRepository + Send + Sync>,
+    pub processed_jobs_tracker: Arc<dyn ProcessedJobsTrackerTrait + Send + Sync>,
+    pub anchor_store: Arc<dyn AnchorStore + Send + Sync>,
+    pub fee_bump_config: FeeBumpConfig,
+    pub worker_metrics: Arc<WorkerMetrics>,
 }
 
 async fn health_check() -> StatusCode {
     StatusCode::OK
 }
 
-async fn status(_state: State<Arc<AppState>>) -> Json<serde_json::Value> {
+async fn status(State(state): State<Arc<AppState>>) -> Json<serde_json::Value> {
     let response = serde_json::json!({
         "status": "healthy",
         "service": "rust-polling",
         "database": "connected",
         "redis": "connected",
-        "blockchain": "simulated"
+        "blockchain": state.blockchain_mode.as_str(),
+        "worker": {
+            "queue_depth": state.worker_metrics.queue_depth(),
+            "in_flight": state.worker_metrics.in_flight(),
+            "poll_interval_seconds": state.worker_metrics.poll_interval_seconds(),
+            "idle_polls": state.worker_metrics.idle_polls(),
+        }
     });
     
     Json(response)
@@ -45,6 +85,12 @@ pub fn create_router(state: Arc<AppState>) -> Router {
     Router::new()
         .route("/health", get(health_check))
         .route("/status", get(status))
+        .route("/jobs", get(jobs::list_jobs))
+        .route("/jobs/scheduled", get(jobs::list_scheduled))
+        .route("/jobs/{record_id}/cancel", post(jobs::cancel_job))
+        .route("/records/{id}/verify", post(records::verify_record))
+        .route("/dead-letters", get(dead_letters::list_dead_letters))
+        .route("/dead-letters/{id}/replay", post(dead_letters::replay_dead_letter))
         .with_state(state)
 }
 
@@ -52,41 +98,217 @@ pub async fn start_server(
     config: Config,
     db_pool: PgPool,
     redis_client: Client,
-    blockchain_client: BlockchainClient,
+    blockchain_client: Arc<dyn BlockchainService + Send + Sync>,
 ) -> AppResult<()> {
+    let transaction_repository = Arc::new(PostgresTransactionRepository::new(db_pool.clone()));
+    let processed_jobs_tracker = Arc::new(ProcessedJobsTracker::new(db_pool.clone()));
+    let anchor_store = Arc::new(AnchorRepository::new(db_pool.clone()));
+    let cursor_store = Arc::new(CursorRepository::new(db_pool.clone()));
+    let worker_metrics = Arc::new(WorkerMetrics::default());
+
     let state = Arc::new(AppState {
         db_pool: db_pool.clone(),
         redis_client: redis_client.clone(),
         blockchain_client: blockchain_client.clone(),
+        blockchain_mode: config.blockchain.mode,
+        transaction_repository: transaction_repository.clone(),
+        processed_jobs_tracker: processed_jobs_tracker.clone(),
+        anchor_store: anchor_store.clone(),
+        fee_bump_config: config.fee_bump.clone(),
+        worker_metrics: worker_metrics.clone(),
     });
 
     let app = create_router(state.clone());
     
     info!("Starting web server on http://{}:{}", config.server.host, config.server.port);
     
-    let transaction_repository = Arc::new(PostgresTransactionRepository::new(db_pool.clone()));
-    let processed_jobs_tracker = Arc::new(ProcessedJobsTracker::new(db_pool.clone()));
-    let blockchain_service = Arc::new(blockchain_client);
-    
-    let transaction_processor = Arc::new(TransactionProcessorService::new(
-        processed_jobs_tracker,
-        blockchain_service,
-    ));
-    
-    let mut worker = PollingWorker::new(
-        config.worker,
-        transaction_repository,
-        transaction_processor,
-    );
-    
-    tokio::spawn(async move {
+    let instance_id = config.worker.instance_id.clone();
+    let drain_timeout = Duration::from_secs(config.worker.drain_timeout_seconds);
+    let shutdown = CancellationToken::new();
+    let mut background_workers = Vec::new();
+
+    let mut worker: Box<dyn AppService + Send> = match config.worker.mode {
+        WorkerMode::Transfer => {
+            let transaction_processor = Arc::new(TransactionProcessorService::new(
+                processed_jobs_tracker.clone(),
+                blockchain_client.clone(),
+                RetryPolicy::new(&config.retry),
+                config.priority,
+            ));
+
+            let mut retry_worker = RetryWorker::new(
+                config.retry,
+                transaction_repository.clone(),
+                processed_jobs_tracker.clone(),
+                transaction_processor.clone(),
+            )
+            .with_shutdown(shutdown.clone());
+
+            background_workers.push((
+                "retry worker",
+                tokio::spawn(async move {
+                    if let Err(e) = retry_worker.start().await {
+                        tracing::error!("Retry worker error: {}", e);
+                    }
+                }),
+            ));
+
+            let listen_enabled = config.worker.listen_enabled;
+            let mut polling_worker = PollingWorker::new(
+                config.worker,
+                transaction_repository,
+                transaction_processor,
+                cursor_store,
+            )
+            .with_metrics(worker_metrics)
+            .with_priorities(config.priority)
+            .with_shutdown(shutdown.clone());
+
+            if listen_enabled {
+                match PostgresTransactionListener::connect(&db_pool).await {
+                    Ok(listener) => polling_worker = polling_worker.with_listener(Box::new(listener)),
+                    Err(e) => tracing::warn!("Falling back to pure polling, could not listen: {}", e),
+                }
+            }
+
+            Box::new(polling_worker)
+        }
+        WorkerMode::Anchoring => Box::new(
+            AnchoringWorker::new(
+                config.worker,
+                config.anchoring,
+                transaction_repository,
+                processed_jobs_tracker.clone(),
+                anchor_store,
+                blockchain_client.clone(),
+                cursor_store,
+            )
+            .with_retry_policy(RetryPolicy::new(&config.retry))
+            .with_priorities(config.priority)
+            .with_shutdown(shutdown.clone()),
+        ),
+    };
+
+    let worker = tokio::spawn(async move {
         if let Err(e) = worker.start().await {
             tracing::error!("Worker error: {}", e);
         }
     });
 
+    let leader_lease = Duration::from_millis(config.redis.leader_lease_ms);
+    let elect = |role: &str| LeaderElection::new(redis_client.clone(), role, instance_id.clone(), leader_lease);
+    let leader_tokens = Arc::new(LeaderTokenRepository::new(db_pool.clone()));
+
+    let mut confirmation_worker = LeaderElectedService::new(
+        "confirmation worker",
+        elect("confirmation_worker"),
+        leader_tokens.clone(),
+        ConfirmationWorker::new(
+            config.confirmation,
+            processed_jobs_tracker.clone(),
+            blockchain_client.clone(),
+        )
+        .with_shutdown(shutdown.clone()),
+    )
+    .with_shutdown(shutdown.clone());
+
+    background_workers.push((
+        "confirmation worker",
+        tokio::spawn(async move {
+            if let Err(e) = confirmation_worker.start().await {
+                tracing::error!("Confirmation worker error: {}", e);
+            }
+        }),
+    ));
+
+    let mut stale_job_worker = LeaderElectedService::new(
+        "stale job worker",
+        elect("stale_job_worker"),
+        leader_tokens.clone(),
+        StaleJobWorker::new(
+            config.stale_jobs,
+            processed_jobs_tracker.clone(),
+            blockchain_client.clone(),
+        )
+        .with_shutdown(shutdown.clone()),
+    )
+    .with_shutdown(shutdown.clone());
+
+    background_workers.push((
+        "stale job worker",
+        tokio::spawn(async move {
+            if let Err(e) = stale_job_worker.start().await {
+                tracing::error!("Stale job worker error: {}", e);
+            }
+        }),
+    ));
+
+    let mut fee_bump_worker = LeaderElectedService::new(
+        "fee bump worker",
+        elect("fee_bump_worker"),
+        leader_tokens.clone(),
+        FeeBumpWorker::new(config.fee_bump, processed_jobs_tracker, blockchain_client.clone())
+            .with_shutdown(shutdown.clone()),
+    )
+    .with_shutdown(shutdown.clone());
+
+    background_workers.push((
+        "fee bump worker",
+        tokio::spawn(async move {
+            if let Err(e) = fee_bump_worker.start().await {
+                tracing::error!("Fee bump worker error: {}", e);
+            }
+        }),
+    ));
+
+    let mut nonce_worker = LeaderElectedService::new(
+        "nonce worker",
+        elect("nonce_worker"),
+        leader_tokens.clone(),
+        NonceWorker::new(config.blockchain, blockchain_client).with_shutdown(shutdown.clone()),
+    )
+    .with_shutdown(shutdown.clone());
+
+    background_workers.push((
+        "nonce worker",
+        tokio::spawn(async move {
+            if let Err(e) = nonce_worker.start().await {
+                tracing::error!("Nonce worker error: {}", e);
+            }
+        }),
+    ));
+
+    tokio::spawn(cancel_on_signal(shutdown.clone()));
+
+    // Shutdown order: the web server stops accepting requests, the worker
+    // finishes its in-flight jobs, then the background workers stop
     let listener = tokio::net::TcpListener::bind(format!("{}:{}", config.server.host, config.server.port)).await?;
-    axum::serve(listener, app).await?;
+    let served = axum::serve(listener, app)
+        .with_graceful_shutdown(shutdown.clone().cancelled_owned())
+        .await;
+    shutdown.cancel();
+    info!("Web server stopped, draining workers for up to {}s...", drain_timeout.as_secs());
+
+    let deadline = Instant::now() + drain_timeout;
+    drain("worker", worker, deadline).await;
+    for (name, handle) in background_workers {
+        drain(name, handle, deadline).await;
+    }
 
+    info!("Shutdown complete");
+    served?;
     Ok(())
-} 
\ No newline at end of file
+}
+
+/// Waits for a worker task to finish until `deadline`, then aborts it. Jobs it
+/// leaves behind are picked up again through lease expiry and the stale job worker.
+async fn drain(name: &str, mut handle: JoinHandle<()>, deadline: Instant) {
+    match tokio::time::timeout_at(deadline, &mut handle).await {
+        Ok(Ok(())) => info!("Stopped {}", name),
+        Ok(Err(e)) => tracing::error!("{} panicked: {}", name, e),
+        Err(_) => {
+            warn!("{} did not drain before the deadline, aborting it", name);
+            handle.abort();
+        }
+    }
+}
@@ -0,0 +1,62 @@
+use crate::{
+    api::routes::AppState,
+    error::{AppError, AppResult},
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct DeadLetterQuery {
+    #[serde(default)]
+    pub include_replayed: bool,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+/// Lists dead-lettered jobs, newest first
+pub async fn list_dead_letters(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<DeadLetterQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    let dead_letters = state
+        .processed_jobs_tracker
+        .fetch_dead_letters(query.include_replayed, query.limit.clamp(1, 1000))
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "count": dead_letters.len(),
+        "dead_letters": dead_letters,
+    })))
+}
+
+/// Requeues a dead-lettered job, typically after the source row was fixed.
+/// The retry worker picks it up with a fresh attempt budget and re-reads the row.
+pub async fn replay_dead_letter(
+    State(state): State<Arc<AppState>>,
+    Path(id): Path<i64>,
+) -> AppResult<Json<serde_json::Value>> {
+    let tracker = &state.processed_jobs_tracker;
+
+    let dead_letter = tracker
+        .find_dead_letter(id)
+        .await?
+        .ok_or_else(|| AppError::NotFound(format!("Dead letter {} does not exist", id)))?;
+
+    if dead_letter.replayed_at.is_some() || !tracker.replay_dead_letter(id).await? {
+        return Err(AppError::Conflict(format!("Dead letter {} was already replayed", id)));
+    }
+
+    Ok(Json(serde_json::json!({
+        "id": id,
+        "record_id": dead_letter.record_id,
+        "status": "requeued",
+    })))
+}
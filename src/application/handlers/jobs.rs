@@ -0,0 +1,171 @@
+use crate::{
+    api::routes::AppState,
+    domain::models::processed_job::{decode_hash, JobStatus},
+    error::{AppError, AppResult},
+};
+use axum::{
+    extract::{Path, Query, State},
+    response::Json,
+};
+use serde::Deserialize;
+use std::sync::Arc;
+
+#[derive(Debug, Deserialize)]
+pub struct JobsQuery {
+    #[serde(default)]
+    pub status: Option<JobStatus>,
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScheduledQuery {
+    #[serde(default = "default_limit")]
+    pub limit: i64,
+}
+
+fn default_limit() -> i64 {
+    100
+}
+
+/// Lists jobs, most recently updated first, optionally filtered by status
+pub async fn list_jobs(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<JobsQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    let jobs = state
+        .processed_jobs_tracker
+        .get_all_processed(query.status, query.limit.clamp(1, 1000))
+        .await?;
+
+    Ok(Json(serde_json::json!({
+        "count": jobs.len(),
+        "jobs": jobs,
+    })))
+}
+
+/// Lists pending transactions that are not due yet, soonest first
+pub async fn list_scheduled(
+    State(state): State<Arc<AppState>>,
+    Query(query): Query<ScheduledQuery>,
+) -> AppResult<Json<serde_json::Value>> {
+    let transactions = state
+        .transaction_repository
+        .fetch_scheduled_transactions(query.limit.clamp(1, 1000))
+        .await?;
+
+    let scheduled: Vec<_> = transactions
+        .into_iter()
+        .map(|transaction| {
+            serde_json::json!({
+                "record_id": transaction.id,
+                "execute_after": transaction.execute_after,
+                "priority": transaction.priority,
+                "created_at": transaction.created_at,
+                "payload": transaction.payload,
+            })
+        })
+        .collect();
+
+    Ok(Json(serde_json::json!({
+        "count": scheduled.len(),
+        "scheduled": scheduled,
+    })))
+}
+
+/// Cancels a job: unbroadcast jobs are dropped, releasing the nonce of one that
+/// was already signed, and jobs waiting in the mempool are replaced by a
+/// zero-value self transfer at the same nonce. Those stay cancelling until the
+/// confirmation worker sees which of the two was mined. A record anchored together with
+/// others cannot be cancelled on its own, since replacing the shared
+/// transaction would strand the rest of its batch.
+pub async fn cancel_job(
+    State(state): State<Arc<AppState>>,
+    Path(record_id): Path<i32>,
+) -> AppResult<Json<serde_json::Value>> {
+    if state.transaction_repository.find_transaction(record_id).await?.is_none() {
+        return Err(AppError::NotFound(format!("Transaction {} does not exist", record_id)));
+    }
+
+    let record_id = record_id as i64;
+    let tracker = &state.processed_jobs_tracker;
+
+    if tracker.cancel_unbroadcast(record_id).await? {
+        return Ok(Json(serde_json::json!({
+            "record_id": record_id,
+            "status": JobStatus::Cancelled,
+            "action": "dropped",
+        })));
+    }
+
+    match tracker.get_status(record_id).await? {
+        Some((status @ (JobStatus::Signed | JobStatus::Broadcast), Some(tx_hash))) => {
+            let sharing = tracker.count_jobs_sharing(&tx_hash).await?;
+            if sharing > 1 {
+                return Err(AppError::Conflict(format!(
+                    "Transaction {} of record {} anchors {} records and cannot be cancelled for one of them",
+                    tx_hash, record_id, sharing
+                )));
+            }
+
+            // A signed job may have been broadcast before its status caught up,
+            // so only drop it once the node has never seen its transaction
+            let tx_hash_bytes = decode_hash(&tx_hash)?;
+            if status == JobStatus::Signed && state.blockchain_client.get_transaction(tx_hash_bytes).await?.is_none() {
+                return drop_signed(&state, record_id, &tx_hash).await;
+            }
+
+            let cancellation = state
+                .blockchain_client
+                .sign_cancellation(
+                    tx_hash_bytes,
+                    state.fee_bump_config.bump_percent,
+                    state.fee_bump_config.max_fee_per_gas_wei,
+                )
+                .await?
+                .ok_or_else(|| {
+                    AppError::Conflict(format!(
+                        "Transaction {} of record {} is no longer pending in the mempool",
+                        tx_hash, record_id
+                    ))
+                })?;
+
+            tracker.mark_cancelling(record_id, &tx_hash, &cancellation).await?;
+            state.blockchain_client.broadcast_transaction(&cancellation).await?;
+
+            Ok(Json(serde_json::json!({
+                "record_id": record_id,
+                "status": JobStatus::Cancelling,
+                "action": "replaced",
+                "cancelled_tx_hash": tx_hash,
+                "tx_hash": cancellation.tx_hash_hex(),
+                "nonce": cancellation.nonce,
+            })))
+        }
+        Some((status, _)) => Err(AppError::Conflict(format!(
+            "Record {} is already {} and cannot be cancelled",
+            record_id, status
+        ))),
+        None => Err(AppError::Conflict(format!("Record {} could not be cancelled", record_id))),
+    }
+}
+
+/// Drops a job whose signed transaction never reached the node and releases
+/// its nonce, which the nonce worker then closes with a self transfer
+async fn drop_signed(state: &AppState, record_id: i64, tx_hash: &str) -> AppResult<Json<serde_json::Value>> {
+    let Some(nonce) = state.processed_jobs_tracker.cancel_signed(record_id, tx_hash).await? else {
+        return Err(AppError::Conflict(format!(
+            "Record {} moved on from signed while it was being cancelled",
+            record_id
+        )));
+    };
+    state.blockchain_client.release_nonce(nonce as u64).await?;
+
+    Ok(Json(serde_json::json!({
+        "record_id": record_id,
+        "status": JobStatus::Cancelled,
+        "action": "dropped",
+        "cancelled_tx_hash": tx_hash,
+        "released_nonce": nonce,
+    })))
+}
@@ -1 +1,3 @@
-// Health and status handlers will be added here 
\ No newline at end of file
+pub mod dead_letters;
+pub mod jobs;
+pub mod records;
\ No newline at end of file
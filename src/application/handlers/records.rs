@@ -0,0 +1,80 @@
+use crate::{
+    api::routes::AppState,
+    domain::{
+        models::processed_job::decode_hash,
+        services::merkle::{calldata_commits_to, hash_payload, MerkleTree},
+    },
+    error::{AppError, AppResult},
+};
+use axum::{
+    extract::{Path, State},
+    response::Json,
+};
+use std::sync::Arc;
+
+/// Checks a payload against what was published for record `id`: hashes it,
+/// folds in the stored Merkle proof when the record was anchored, and looks for
+/// the result in the calldata of the on-chain transaction. A record that was
+/// sent as a plain transfer published no hash, so it cannot be verified at all.
+pub async fn verify_record(
+    State(state): State<Arc<AppState>>,
+    Path(record_id): Path<i64>,
+    Json(payload): Json<serde_json::Value>,
+) -> AppResult<Json<serde_json::Value>> {
+    let tx_hash = match state.processed_jobs_tracker.get_status(record_id).await? {
+        Some((_, Some(tx_hash))) => tx_hash,
+        _ => {
+            return Err(AppError::NotFound(format!(
+                "Record {} has not been published",
+                record_id
+            )))
+        }
+    };
+
+    let payload_hash = hash_payload(&payload);
+    let proof = state.anchor_store.find_proof(record_id).await?;
+    let commitment = match &proof {
+        Some(leaf) => MerkleTree::compute_root(payload_hash, &leaf.proof),
+        None => payload_hash,
+    };
+
+    let published = state
+        .blockchain_client
+        .get_transaction(decode_hash(&tx_hash)?)
+        .await?;
+
+    if let Some(tx) = published.as_ref().filter(|tx| proof.is_none() && tx.input.len() < 32) {
+        return Err(AppError::Conflict(format!(
+            "Record {} is not anchored: transaction {} carries no payload hash ({} bytes of calldata)",
+            record_id,
+            tx_hash,
+            tx.input.len()
+        )));
+    }
+
+    let verified = published
+        .as_ref()
+        .is_some_and(|tx| tx.block_number.is_some() && calldata_commits_to(&tx.input, &commitment));
+    let block_number = published.as_ref().and_then(|tx| tx.block_number);
+    let block_timestamp = published.as_ref().and_then(|tx| tx.block_timestamp);
+
+    Ok(Json(serde_json::json!({
+        "record_id": record_id,
+        "verified": verified,
+        "payload_hash": format!("0x{}", hex::encode(payload_hash)),
+        "merkle_root": proof.as_ref().map(|_| format!("0x{}", hex::encode(commitment))),
+        "merkle_proof": proof.as_ref().map(|leaf| {
+            leaf.proof
+                .iter()
+                .map(|hash| format!("0x{}", hex::encode(hash)))
+                .collect::<Vec<_>>()
+        }),
+        "tx_hash": tx_hash,
+        "found_on_chain": published.is_some(),
+        "block_number": block_number,
+        "block_timestamp": block_timestamp,
+        "block_time": block_timestamp
+            .and_then(|ts| chrono::DateTime::from_timestamp(ts as i64, 0))
+            .map(|time| time.to_rfc3339()),
+    })))
+}
@@ -0,0 +1,376 @@
+use crate::{
+    application::worker::polling_worker::full_sweep_due,
+    config::{AnchoringConfig, PriorityConfig, WorkerConfig},
+    domain::{
+        models::{
+            anchor::{AnchorBatch, AnchorLeaf},
+            broadcast::SignedTransaction,
+            transaction::{PriorityFee, Transaction},
+        },
+        services::{
+            abi_encoder::encode_call,
+            merkle::{hash_payload, MerkleTree},
+            retry_policy::RetryPolicy,
+        },
+    },
+    error::{AppError, AppResult},
+    shared::{
+        shutdown::sleep_or_shutdown,
+        traits::{
+            AnchorStore, AppService, BlockchainService, CursorStore, ProcessedJobsTracker, TransactionRepository,
+        },
+    },
+};
+use alloy::primitives::{Address, Bytes, U256};
+use std::{str::FromStr, sync::Arc, time::{Duration, Instant}};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// Name under which the worker's position is persisted
+const CURSOR_NAME: &str = "anchoring_worker";
+
+/// A record waiting for the next batch, with its earlier failed attempts
+struct BufferedRecord {
+    transaction: Transaction,
+    attempts: i32,
+}
+
+/// A worker that collects new transactions and publishes only the Merkle root
+/// of their payload hashes, once `batch_size` rows are buffered or the oldest
+/// buffered row has waited `max_wait_seconds`
+pub struct AnchoringWorker {
+    config: WorkerConfig,
+    anchoring: AnchoringConfig,
+    transaction_repository: Arc<dyn TransactionRepository + Send + Sync>,
+    processed_jobs_tracker: Arc<dyn ProcessedJobsTracker + Send + Sync>,
+    anchor_store: Arc<dyn AnchorStore + Send + Sync>,
+    blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+    cursor_store: Arc<dyn CursorStore + Send + Sync>,
+    retry_policy: Option<RetryPolicy>,
+    priority: Option<PriorityConfig>,
+    cursor: Option<i64>,
+    last_full_sweep: Option<Instant>,
+    buffer: Vec<BufferedRecord>,
+    oldest_buffered: Option<Instant>,
+    shutdown: CancellationToken,
+}
+
+impl AnchoringWorker {
+    /// Creates a new anchoring worker
+    pub fn new(
+        config: WorkerConfig,
+        anchoring: AnchoringConfig,
+        transaction_repository: Arc<dyn TransactionRepository + Send + Sync>,
+        processed_jobs_tracker: Arc<dyn ProcessedJobsTracker + Send + Sync>,
+        anchor_store: Arc<dyn AnchorStore + Send + Sync>,
+        blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+        cursor_store: Arc<dyn CursorStore + Send + Sync>,
+    ) -> Self {
+        Self {
+            config,
+            anchoring,
+            transaction_repository,
+            processed_jobs_tracker,
+            anchor_store,
+            blockchain_service,
+            cursor_store,
+            retry_policy: None,
+            priority: None,
+            cursor: None,
+            last_full_sweep: None,
+            buffer: Vec::new(),
+            oldest_buffered: None,
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Buffers new transactions and publishes a batch when it is full or old enough
+    async fn poll_once(&mut self) -> AppResult<()> {
+        // A periodic scan from the first row catches inserts that committed behind the cursor
+        let after_id = match self.cursor {
+            _ if full_sweep_due(self.last_full_sweep, self.config.full_sweep_interval_seconds) => {
+                self.last_full_sweep = Some(Instant::now());
+                0
+            }
+            Some(last_id) => last_id,
+            None => self.cursor_store.load_cursor(CURSOR_NAME).await?,
+        };
+
+        let transactions = self
+            .transaction_repository
+            .claim_new_transactions(
+                &self.config.instance_id,
+                after_id,
+                None,
+                self.config.batch_size,
+                self.config.lease_seconds,
+            )
+            .await?;
+
+        for transaction in transactions {
+            let claimed = self.processed_jobs_tracker.claim(transaction.id as i64).await?;
+            self.transaction_repository
+                .complete_transaction(transaction.id, &self.config.instance_id)
+                .await?;
+
+            if claimed {
+                self.push(transaction, 0).await?;
+            }
+        }
+
+        self.cursor = Some(self.cursor_store.advance_cursor(CURSOR_NAME).await?);
+        self.buffer_retries().await?;
+
+        let batch_full = self.buffer.len() >= self.anchoring.batch_size;
+        let waited_long_enough = self
+            .oldest_buffered
+            .is_some_and(|since| since.elapsed() >= Duration::from_secs(self.anchoring.max_wait_seconds));
+
+        if !self.buffer.is_empty() && (batch_full || waited_long_enough) {
+            self.flush().await?;
+        }
+
+        Ok(())
+    }
+
+    /// Schedules records of failed batches for another attempt instead of failing them for good
+    pub fn with_retry_policy(mut self, retry_policy: RetryPolicy) -> Self {
+        self.retry_policy = Some(retry_policy);
+        self
+    }
+
+    /// Pays the tip of the most urgent lane found in each batch
+    pub fn with_priorities(mut self, priority: PriorityConfig) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Stops claiming once `shutdown` is cancelled and publishes whatever is buffered
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Buffers a claimed record. Any JSON payload can be hashed, so buffering is validation.
+    async fn push(&mut self, transaction: Transaction, attempts: i32) -> AppResult<()> {
+        if !self.processed_jobs_tracker.mark_validated(transaction.id as i64).await? {
+            warn!("Record {} left the claimed state before validation. Skipping.", transaction.id);
+            return Ok(());
+        }
+
+        self.oldest_buffered.get_or_insert_with(Instant::now);
+        self.buffer.push(BufferedRecord { transaction, attempts });
+        Ok(())
+    }
+
+    /// Puts failed records whose backoff has elapsed back into the buffer
+    async fn buffer_retries(&mut self) -> AppResult<()> {
+        let record_ids = self
+            .processed_jobs_tracker
+            .fetch_retryable_jobs(self.config.batch_size)
+            .await?;
+
+        for record_id in record_ids {
+            let Some(attempts) = self.processed_jobs_tracker.claim_retry(record_id).await? else {
+                continue;
+            };
+
+            match self.transaction_repository.find_transaction(record_id as i32).await? {
+                Some(transaction) => self.push(transaction, attempts).await?,
+                None => {
+                    warn!("Record {} no longer exists, cannot retry it", record_id);
+                    self.processed_jobs_tracker
+                        .dead_letter(record_id, "record no longer exists")
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Publishes the Merkle root of up to `batch_size` buffered transactions
+    async fn flush(&mut self) -> AppResult<()> {
+        let take = self.buffer.len().min(self.anchoring.batch_size);
+        let batch: Vec<BufferedRecord> = self.buffer.drain(..take).collect();
+        self.oldest_buffered = (!self.buffer.is_empty()).then(Instant::now);
+
+        let leaf_hashes: Vec<[u8; 32]> = batch
+            .iter()
+            .map(|record| hash_payload(&record.transaction.payload))
+            .collect();
+        let tree = MerkleTree::new(leaf_hashes.clone());
+        let merkle_root = tree.root();
+
+        info!(
+            "Anchoring {} records with Merkle root 0x{}",
+            batch.len(),
+            hex::encode(merkle_root)
+        );
+
+        let priority_fee = self
+            .priority
+            .as_ref()
+            .zip(batch.iter().map(|record| record.transaction.priority).max())
+            .map_or(PriorityFee::ESTIMATE, |(priority, most_urgent)| priority.lane(most_urgent).priority_fee);
+
+        let signed = match self.sign_root(merkle_root, priority_fee).await {
+            Ok(signed) => signed,
+            Err(e) => {
+                error!("Failed to sign Merkle root: {}", e);
+                self.fail_batch(&batch, &e).await?;
+                return Err(e);
+            }
+        };
+
+        let leaves = batch
+            .iter()
+            .zip(leaf_hashes)
+            .enumerate()
+            .map(|(leaf_index, (record, leaf_hash))| AnchorLeaf {
+                record_id: record.transaction.id as i64,
+                leaf_index,
+                leaf_hash,
+                proof: tree.proof(leaf_index),
+            })
+            .collect();
+
+        // Proofs are saved before any record is signed, since the stale job
+        // worker rebroadcasts a signed root without ever coming back here
+        let saved = self
+            .anchor_store
+            .save_batch(&AnchorBatch {
+                merkle_root,
+                tx_hash: signed.tx_hash_hex(),
+                leaves,
+            })
+            .await;
+        if let Err(e) = saved {
+            error!("Failed to save anchor batch for Merkle root 0x{}: {}", hex::encode(merkle_root), e);
+            self.release_nonce(signed.nonce).await;
+            self.fail_batch(&batch, &e).await?;
+            return Err(e);
+        }
+
+        let mut recorded = false;
+        for record in &batch {
+            match self.processed_jobs_tracker.mark_signed(record.transaction.id as i64, &signed).await {
+                Ok(true) => recorded = true,
+                Ok(false) => warn!("Record {} left the validated state before signing", record.transaction.id),
+                Err(e) => {
+                    // A signed record gets the root rebroadcast by the stale job worker,
+                    // without one nothing would ever use the nonce
+                    if !recorded {
+                        self.release_nonce(signed.nonce).await;
+                    }
+                    return Err(e);
+                }
+            }
+        }
+
+        let sent = match self.blockchain_service.broadcast_transaction(&signed).await {
+            Ok(sent) => sent,
+            Err(e) => {
+                // The records stay signed so the stale job worker rebroadcasts this exact anchor
+                error!("Failed to broadcast Merkle root {}, leaving it signed: {}", signed.tx_hash_hex(), e);
+                return Err(e);
+            }
+        };
+
+        for record in &batch {
+            self.processed_jobs_tracker
+                .mark_broadcast(record.transaction.id as i64, &sent)
+                .await?;
+        }
+
+        Ok(())
+    }
+
+    /// Releases the nonce of a root no record holds, logging rather than failing
+    async fn release_nonce(&self, nonce: u64) {
+        if let Err(e) = self.blockchain_service.release_nonce(nonce).await {
+            error!("Failed to release nonce {}: {}", nonce, e);
+        }
+    }
+
+    /// Schedules every record of a batch that could not be published for a
+    /// retry, or dead-letters it when no retry is allowed
+    async fn fail_batch(&self, batch: &[BufferedRecord], error: &AppError) -> AppResult<()> {
+        for record in batch {
+            let record_id = record.transaction.id as i64;
+            let next_attempt_at = self
+                .retry_policy
+                .as_ref()
+                .and_then(|policy| policy.next_attempt_at(record.attempts + 1, error));
+
+            match next_attempt_at {
+                Some(at) => {
+                    self.processed_jobs_tracker
+                        .mark_failed(record_id, &error.to_string(), at)
+                        .await?
+                }
+                None => {
+                    self.processed_jobs_tracker
+                        .dead_letter(record_id, &error.to_string())
+                        .await?;
+                }
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Signs a call to `anchor(bytes32)` on the configured contract, or a zero-value
+    /// self transfer carrying the root as calldata when no contract is configured
+    async fn sign_root(&self, merkle_root: [u8; 32], priority_fee: PriorityFee) -> AppResult<SignedTransaction> {
+        let root_hex = format!("0x{}", hex::encode(merkle_root));
+
+        let (to, data) = match &self.anchoring.contract {
+            Some(contract) => {
+                let to = Address::from_str(contract)
+                    .map_err(|e| AppError::Config(format!("Invalid ANCHOR_CONTRACT: {}", e)))?;
+                (to, encode_call("anchor(bytes32)", &[serde_json::Value::String(root_hex)])?)
+            }
+            None => (self.blockchain_service.signer_address(), Bytes::from(merkle_root)),
+        };
+
+        self.blockchain_service.sign_contract_call(to, U256::ZERO, data, priority_fee).await
+    }
+}
+
+#[async_trait::async_trait]
+impl AppService for AnchoringWorker {
+    async fn start(&mut self) -> AppResult<()> {
+        info!(
+            "Starting anchoring worker (batch size {}, max wait {}s)...",
+            self.anchoring.batch_size, self.anchoring.max_wait_seconds
+        );
+
+        loop {
+            if let Err(e) = self.poll_once().await {
+                error!("Error during anchoring: {}", e);
+            }
+
+            if !sleep_or_shutdown(&self.shutdown, Duration::from_secs(self.config.poll_interval_seconds)).await {
+                break;
+            }
+        }
+
+        if !self.buffer.is_empty() {
+            info!("Publishing {} buffered records before shutting down...", self.buffer.len());
+            self.flush().await?;
+        }
+
+        info!("Anchoring worker drained");
+        Ok(())
+    }
+
+    async fn stop(&self) -> AppResult<()> {
+        if !self.buffer.is_empty() {
+            warn!("Stopping anchoring worker with {} unpublished records", self.buffer.len());
+        }
+        info!("Stopping anchoring worker...");
+        self.shutdown.cancel();
+        Ok(())
+    }
+}
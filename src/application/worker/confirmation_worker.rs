@@ -0,0 +1,214 @@
+use crate::{
+    config::ConfirmationConfig,
+    domain::models::{
+        leadership::FencingToken,
+        processed_job::{decode_hash, ConfirmedJob, SentJob},
+        receipt::TransactionReceipt,
+    },
+    error::AppResult,
+    shared::{
+        shutdown::sleep_or_shutdown,
+        traits::{AppService, BlockchainService, Fenced, ProcessedJobsTracker},
+    },
+};
+use std::{collections::HashMap, sync::Arc, time::Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+/// A worker that follows sent transactions until they are confirmed or reverted,
+/// and re-checks recently confirmed ones against the canonical chain
+pub struct ConfirmationWorker {
+    config: ConfirmationConfig,
+    processed_jobs_tracker: Arc<dyn ProcessedJobsTracker + Send + Sync>,
+    blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+    shutdown: CancellationToken,
+    fencing_token: Option<FencingToken>,
+}
+
+impl ConfirmationWorker {
+    /// Creates a new confirmation worker
+    pub fn new(
+        config: ConfirmationConfig,
+        processed_jobs_tracker: Arc<dyn ProcessedJobsTracker + Send + Sync>,
+        blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+    ) -> Self {
+        Self {
+            config,
+            processed_jobs_tracker,
+            blockchain_service,
+            shutdown: CancellationToken::new(),
+            fencing_token: None,
+        }
+    }
+
+    /// Stops the worker between passes once `shutdown` is cancelled
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Runs one reorg check followed by one receipt check
+    async fn check_once(&self) -> AppResult<()> {
+        let head = self.blockchain_service.get_block_number().await?;
+
+        self.check_reorgs(head).await?;
+        self.check_receipts(head).await
+    }
+
+    /// Moves confirmed and cancelled jobs whose inclusion block is no longer
+    /// canonical back to broadcast or cancelling, newest blocks first, looking
+    /// up each block only once
+    async fn check_reorgs(&self, head: u64) -> AppResult<()> {
+        let min_block = head.saturating_sub(self.config.reorg_depth);
+        let confirmed_jobs = self
+            .processed_jobs_tracker
+            .fetch_confirmed_since(min_block, self.config.batch_size)
+            .await?;
+        let mut canonical_hashes = HashMap::new();
+
+        for job in confirmed_jobs {
+            if let Err(e) = self.check_inclusion(&job, &mut canonical_hashes).await {
+                error!("Error checking inclusion of record {}: {}", job.record_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn check_inclusion(
+        &self,
+        job: &ConfirmedJob,
+        canonical_hashes: &mut HashMap<i64, Option<[u8; 32]>>,
+    ) -> AppResult<()> {
+        let canonical_hash = match canonical_hashes.get(&job.block_number) {
+            Some(hash) => *hash,
+            None => {
+                let hash = self
+                    .blockchain_service
+                    .get_block_hash(job.block_number as u64)
+                    .await?;
+                canonical_hashes.insert(job.block_number, hash);
+                hash
+            }
+        };
+
+        if canonical_hash == Some(job.block_hash_bytes()?) {
+            return Ok(());
+        }
+
+        warn!(
+            "Block {} of record {} is no longer canonical (stored {})",
+            job.block_number, job.record_id, job.block_hash
+        );
+        self.processed_jobs_tracker
+            .mark_reorged(job, canonical_hash, self.fencing_token.as_ref())
+            .await
+    }
+
+    /// Checks the receipt of every sent job once
+    async fn check_receipts(&self, head: u64) -> AppResult<()> {
+        let sent_jobs = self
+            .processed_jobs_tracker
+            .fetch_sent_jobs(self.config.batch_size)
+            .await?;
+
+        if sent_jobs.is_empty() {
+            return Ok(());
+        }
+
+        info!("Checking receipts for {} sent jobs...", sent_jobs.len());
+
+        for job in sent_jobs {
+            if let Err(e) = self.check_job(&job, head).await {
+                error!("Error checking receipt for record {}: {}", job.record_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Looks for a receipt of any transaction broadcast for the job, since a
+    /// fee-bumped replacement or the original may be the one that gets mined
+    async fn find_receipt(&self, job: &SentJob) -> AppResult<Option<TransactionReceipt>> {
+        for tx_hash in &job.tx_hashes {
+            let receipt = self
+                .blockchain_service
+                .get_transaction_receipt(decode_hash(tx_hash)?)
+                .await?;
+            if receipt.is_some() {
+                return Ok(receipt);
+            }
+        }
+
+        Ok(None)
+    }
+
+    /// Settles a job once any of its transactions is mined: a mined
+    /// cancellation cancels it, otherwise its own transaction decides
+    async fn check_job(&self, job: &SentJob, head: u64) -> AppResult<()> {
+        let Some(receipt) = self.find_receipt(job).await? else {
+            debug!("Transaction {} for record {} is not mined yet", job.tx_hash, job.record_id);
+            return Ok(());
+        };
+
+        let cancelled = job.is_cancellation(&format!("0x{}", hex::encode(receipt.tx_hash)));
+        if !receipt.success && !cancelled {
+            return self
+                .processed_jobs_tracker
+                .mark_reverted(job.record_id, &receipt, self.fencing_token.as_ref())
+                .await;
+        }
+
+        let confirmations = head.saturating_sub(receipt.block_number) + 1;
+        if confirmations < self.config.required_confirmations {
+            debug!(
+                "Record {} has {}/{} confirmations",
+                job.record_id, confirmations, self.config.required_confirmations
+            );
+            return Ok(());
+        }
+
+        if cancelled {
+            return self
+                .processed_jobs_tracker
+                .mark_cancelled(job.record_id, &receipt, self.fencing_token.as_ref())
+                .await;
+        }
+
+        self.processed_jobs_tracker
+            .mark_confirmed(job.record_id, &receipt, self.fencing_token.as_ref())
+            .await
+    }
+}
+
+impl Fenced for ConfirmationWorker {
+    fn set_fencing_token(&mut self, token: FencingToken) {
+        self.fencing_token = Some(token);
+    }
+}
+
+#[async_trait::async_trait]
+impl AppService for ConfirmationWorker {
+    async fn start(&mut self) -> AppResult<()> {
+        info!(
+            "Starting confirmation worker (required confirmations: {})...",
+            self.config.required_confirmations
+        );
+
+        loop {
+            if let Err(e) = self.check_once().await {
+                error!("Error during confirmation check: {}", e);
+            }
+
+            if !sleep_or_shutdown(&self.shutdown, Duration::from_secs(self.config.poll_interval_seconds)).await {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn stop(&self) -> AppResult<()> {
+        info!("Stopping confirmation worker...");
+        self.shutdown.cancel();
+        Ok(())
+    }
+}
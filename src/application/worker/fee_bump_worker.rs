@@ -0,0 +1,132 @@
+use crate::{
+    config::FeeBumpConfig,
+    domain::models::{leadership::FencingToken, processed_job::SentJob},
+    error::AppResult,
+    shared::{
+        shutdown::sleep_or_shutdown,
+        traits::{AppService, BlockchainService, Fenced, ProcessedJobsTracker},
+    },
+};
+use std::{collections::HashSet, sync::Arc, time::Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// A worker that replaces transactions stuck in the mempool with higher-fee copies at the same nonce
+pub struct FeeBumpWorker {
+    config: FeeBumpConfig,
+    processed_jobs_tracker: Arc<dyn ProcessedJobsTracker + Send + Sync>,
+    blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+    shutdown: CancellationToken,
+    fencing_token: Option<FencingToken>,
+}
+
+impl FeeBumpWorker {
+    /// Creates a new fee bump worker
+    pub fn new(
+        config: FeeBumpConfig,
+        processed_jobs_tracker: Arc<dyn ProcessedJobsTracker + Send + Sync>,
+        blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+    ) -> Self {
+        Self {
+            config,
+            processed_jobs_tracker,
+            blockchain_service,
+            shutdown: CancellationToken::new(),
+            fencing_token: None,
+        }
+    }
+
+    /// Stops the worker between passes once `shutdown` is cancelled
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Bumps every job that has been sent for longer than the stuck timeout
+    async fn bump_once(&self) -> AppResult<()> {
+        let sent_before = chrono::Utc::now() - chrono::Duration::seconds(self.config.stuck_after_seconds);
+        let stuck_jobs = self
+            .processed_jobs_tracker
+            .fetch_stuck_jobs(sent_before, self.config.batch_size)
+            .await?;
+
+        // Anchored jobs share one transaction, which only needs replacing once
+        let mut seen = HashSet::new();
+        for job in stuck_jobs {
+            if !seen.insert(job.tx_hash.clone()) {
+                continue;
+            }
+            if let Err(e) = self.bump_job(&job).await {
+                error!("Error bumping fees for record {}: {}", job.record_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    /// Signs a replacement, records it, then broadcasts it, so a replacement
+    /// that reaches the node is always among the hashes the job is watched for.
+    /// One that was recorded but never reached the node is broadcast again
+    /// before anything is bumped further.
+    async fn bump_job(&self, job: &SentJob) -> AppResult<()> {
+        let tx_hash = job.tx_hash_bytes()?;
+
+        if let Some(signed) = job.signed_transaction()? {
+            if self.blockchain_service.get_transaction(tx_hash).await?.is_none() {
+                warn!("Transaction {} of record {} is unknown to the node, broadcasting it again", job.tx_hash, job.record_id);
+                self.blockchain_service.broadcast_transaction(&signed).await?;
+                return Ok(());
+            }
+        }
+
+        let Some(replacement) = self
+            .blockchain_service
+            .sign_replacement(tx_hash, self.config.bump_percent, self.config.max_fee_per_gas_wei)
+            .await?
+        else {
+            return Ok(());
+        };
+
+        let recorded = self
+            .processed_jobs_tracker
+            .record_replacement(&job.tx_hash, &replacement, self.fencing_token.as_ref())
+            .await?;
+        if recorded {
+            self.blockchain_service.broadcast_transaction(&replacement).await?;
+        }
+
+        Ok(())
+    }
+}
+
+impl Fenced for FeeBumpWorker {
+    fn set_fencing_token(&mut self, token: FencingToken) {
+        self.fencing_token = Some(token);
+    }
+}
+
+#[async_trait::async_trait]
+impl AppService for FeeBumpWorker {
+    async fn start(&mut self) -> AppResult<()> {
+        info!(
+            "Starting fee bump worker (stuck after {}s, bump {}%)...",
+            self.config.stuck_after_seconds, self.config.bump_percent
+        );
+
+        loop {
+            if let Err(e) = self.bump_once().await {
+                error!("Error during fee bump sweep: {}", e);
+            }
+
+            if !sleep_or_shutdown(&self.shutdown, Duration::from_secs(self.config.poll_interval_seconds)).await {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn stop(&self) -> AppResult<()> {
+        info!("Stopping fee bump worker...");
+        self.shutdown.cancel();
+        Ok(())
+    }
+}
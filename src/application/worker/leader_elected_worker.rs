@@ -0,0 +1,94 @@
+use crate::{
+    error::AppResult,
+    infrastructure::redis::leader::LeaderElection,
+    shared::{
+        shutdown::sleep_or_shutdown,
+        traits::{AppService, Fenced, LeaderTokenStore},
+    },
+};
+use std::sync::Arc;
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+/// Runs the wrapped service only while this replica holds leadership of its
+/// role. Losing leadership stops the service and the wrapper campaigns again.
+/// Every term registers a new fencing token that the service writes with, so
+/// a leader that stalled past its lease cannot overwrite its successor.
+pub struct LeaderElectedService<S> {
+    election: LeaderElection,
+    leader_tokens: Arc<dyn LeaderTokenStore + Send + Sync>,
+    inner: S,
+    name: &'static str,
+    shutdown: CancellationToken,
+}
+
+impl<S> LeaderElectedService<S> {
+    /// Wraps a service so it runs on exactly one replica at a time
+    pub fn new(
+        name: &'static str,
+        election: LeaderElection,
+        leader_tokens: Arc<dyn LeaderTokenStore + Send + Sync>,
+        inner: S,
+    ) -> Self {
+        Self {
+            election,
+            leader_tokens,
+            inner,
+            name,
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Stops campaigning once `shutdown` is cancelled. The wrapped service
+    /// should watch the same token so its leadership is released after it stops.
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+}
+
+#[async_trait::async_trait]
+impl<S: AppService + Fenced + Send + Sync> AppService for LeaderElectedService<S> {
+    async fn start(&mut self) -> AppResult<()> {
+        info!("Campaigning for leadership of {}...", self.name);
+
+        loop {
+            match self.election.try_acquire().await {
+                Ok(Some(election_token)) => {
+                    let result = match self.leader_tokens.register(self.election.role(), election_token).await {
+                        Ok(fencing_token) => {
+                            info!("Became leader of {} (fencing token {})", self.name, fencing_token.token);
+                            self.inner.set_fencing_token(fencing_token);
+
+                            tokio::select! {
+                                result = self.inner.start() => result,
+                                _ = self.election.hold() => Ok(()),
+                            }
+                        }
+                        Err(e) => Err(e),
+                    };
+
+                    if let Err(e) = result {
+                        error!("{} stopped with an error: {}", self.name, e);
+                    }
+                    if let Err(e) = self.election.release().await {
+                        error!("Could not release leadership of {}: {}", self.name, e);
+                    }
+                    info!("Stepped down as leader of {}", self.name);
+                }
+                Ok(None) => {}
+                Err(e) => error!("Error campaigning for leadership of {}: {}", self.name, e),
+            }
+
+            if !sleep_or_shutdown(&self.shutdown, self.election.renew_interval()).await {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn stop(&self) -> AppResult<()> {
+        self.shutdown.cancel();
+        self.inner.stop().await?;
+        self.election.release().await
+    }
+}
@@ -0,0 +1,59 @@
+use std::{
+    sync::atomic::{AtomicU32, AtomicU64, AtomicUsize, Ordering},
+    time::Duration,
+};
+
+/// Live counters of the polling pipeline, reported on `/status`
+#[derive(Debug, Default)]
+pub struct WorkerMetrics {
+    queue_depth: AtomicUsize,
+    in_flight: AtomicUsize,
+    poll_interval_seconds: AtomicU64,
+    idle_polls: AtomicU32,
+}
+
+impl WorkerMetrics {
+    /// Claimed rows waiting for their sender's lane
+    pub fn queue_depth(&self) -> usize {
+        self.queue_depth.load(Ordering::Relaxed)
+    }
+
+    /// Rows being processed right now
+    pub fn in_flight(&self) -> usize {
+        self.in_flight.load(Ordering::Relaxed)
+    }
+
+    /// Current wait between polls
+    pub fn poll_interval_seconds(&self) -> u64 {
+        self.poll_interval_seconds.load(Ordering::Relaxed)
+    }
+
+    /// Polls in a row that found nothing to claim
+    pub fn idle_polls(&self) -> u32 {
+        self.idle_polls.load(Ordering::Relaxed)
+    }
+
+    pub(crate) fn polled(&self, idle_polls: u32, poll_interval: Duration) {
+        self.idle_polls.store(idle_polls, Ordering::Relaxed);
+        self.poll_interval_seconds.store(poll_interval.as_secs(), Ordering::Relaxed);
+    }
+
+    pub(crate) fn queued(&self, count: usize) {
+        self.queue_depth.fetch_add(count, Ordering::Relaxed);
+    }
+
+    /// Moves a row from the queue to in flight
+    pub(crate) fn started(&self) {
+        self.queue_depth.fetch_sub(1, Ordering::Relaxed);
+        self.in_flight.fetch_add(1, Ordering::Relaxed);
+    }
+
+    pub(crate) fn finished(&self) {
+        self.in_flight.fetch_sub(1, Ordering::Relaxed);
+    }
+
+    /// Removes queued rows that were released without being processed
+    pub(crate) fn dropped(&self, count: usize) {
+        self.queue_depth.fetch_sub(count, Ordering::Relaxed);
+    }
+}
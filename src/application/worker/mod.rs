@@ -1 +1,9 @@
-pub mod polling_worker; 
\ No newline at end of file
+pub mod anchoring_worker;
+pub mod confirmation_worker;
+pub mod fee_bump_worker;
+pub mod leader_elected_worker;
+pub mod metrics;
+pub mod nonce_worker;
+pub mod polling_worker;
+pub mod retry_worker;
+pub mod stale_job_worker;
\ No newline at end of file
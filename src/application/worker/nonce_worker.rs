@@ -0,0 +1,74 @@
+use crate::{
+    config::BlockchainConfig,
+    domain::models::leadership::FencingToken,
+    error::AppResult,
+    shared::{
+        shutdown::sleep_or_shutdown,
+        traits::{AppService, BlockchainService, Fenced},
+    },
+};
+use std::{sync::Arc, time::Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info};
+
+/// A worker that resyncs the signer nonce on start and keeps filling nonce gaps
+pub struct NonceWorker {
+    config: BlockchainConfig,
+    blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+    shutdown: CancellationToken,
+    fencing_token: Option<FencingToken>,
+}
+
+impl NonceWorker {
+    /// Creates a new nonce worker
+    pub fn new(
+        config: BlockchainConfig,
+        blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+    ) -> Self {
+        Self {
+            config,
+            blockchain_service,
+            shutdown: CancellationToken::new(),
+            fencing_token: None,
+        }
+    }
+
+    /// Stops the worker between passes once `shutdown` is cancelled
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+}
+
+impl Fenced for NonceWorker {
+    fn set_fencing_token(&mut self, token: FencingToken) {
+        self.fencing_token = Some(token);
+    }
+}
+
+#[async_trait::async_trait]
+impl AppService for NonceWorker {
+    async fn start(&mut self) -> AppResult<()> {
+        info!("Starting nonce worker...");
+        self.blockchain_service.resync_nonces(self.fencing_token.as_ref()).await?;
+
+        loop {
+            let interval = Duration::from_secs(self.config.nonce_gap_check_interval_seconds);
+            if !sleep_or_shutdown(&self.shutdown, interval).await {
+                return Ok(());
+            }
+
+            match self.blockchain_service.fill_nonce_gaps(self.fencing_token.as_ref()).await {
+                Ok(0) => {}
+                Ok(filled) => info!("Filled {} nonce gaps", filled),
+                Err(e) => error!("Error filling nonce gaps: {}", e),
+            }
+        }
+    }
+
+    async fn stop(&self) -> AppResult<()> {
+        info!("Stopping nonce worker...");
+        self.shutdown.cancel();
+        Ok(())
+    }
+}
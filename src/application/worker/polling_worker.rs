@@ -1,18 +1,45 @@
 use crate::{
-    config::WorkerConfig,
-    error::AppResult,
-    shared::traits::{AppService, TransactionProcessor, TransactionRepository},
+    application::worker::metrics::WorkerMetrics,
+    config::{PriorityConfig, WorkerConfig},
+    domain::models::transaction::{Transaction, TransactionPriority},
+    error::{AppError, AppResult},
+    shared::{
+        shutdown::sleep_or_shutdown,
+        traits::{AppService, CursorStore, TransactionListener, TransactionProcessor, TransactionRepository},
+    },
 };
-use std::{sync::Arc, time::Duration};
-use tracing::{error, info};
+use std::{
+    collections::HashMap,
+    sync::Arc,
+    time::{Duration, Instant},
+};
+use tokio::{sync::Semaphore, task::JoinSet};
+use tokio_util::sync::CancellationToken;
+use tracing::{debug, error, info, warn};
+
+/// Name under which the worker's position is persisted
+const CURSOR_NAME: &str = "polling_worker";
 
-/// A worker that polls the database for new transactions
+/// A worker that polls the database for new transactions.
+/// With a listener attached, notifications trigger a poll immediately and
+/// polling only runs as a slow fallback sweep. Claimed rows of different
+/// senders are processed concurrently, up to `WORKER_CONCURRENCY` at a time.
+/// After several empty polls in a row the wait between polls doubles up to
+/// `WORKER_MAX_POLL_INTERVAL_SECONDS`, and drops back once rows show up.
+/// With priorities configured, each batch is split between the urgency lanes
+/// by weight, so urgent rows go first without starving routine ones.
 pub struct PollingWorker {
     config: WorkerConfig,
     transaction_repository: Arc<dyn TransactionRepository + Send + Sync>,
     transaction_processor: Arc<dyn TransactionProcessor + Send + Sync>,
-    last_checked: chrono::DateTime<chrono::Utc>,
-    running: bool,
+    cursor_store: Arc<dyn CursorStore + Send + Sync>,
+    listener: Option<Box<dyn TransactionListener + Send + Sync>>,
+    priority: Option<PriorityConfig>,
+    metrics: Arc<WorkerMetrics>,
+    shutdown: CancellationToken,
+    cursor: Option<i64>,
+    last_full_sweep: Option<Instant>,
+    idle_polls: u32,
 }
 
 impl PollingWorker {
@@ -21,58 +48,473 @@ impl PollingWorker {
         config: WorkerConfig,
         transaction_repository: Arc<dyn TransactionRepository + Send + Sync>,
         transaction_processor: Arc<dyn TransactionProcessor + Send + Sync>,
+        cursor_store: Arc<dyn CursorStore + Send + Sync>,
     ) -> Self {
-        let lookback_duration = chrono::Duration::hours(config.lookback_hours);
         Self {
             config,
             transaction_repository,
             transaction_processor,
-            last_checked: chrono::Utc::now() - lookback_duration,
-            running: false,
+            cursor_store,
+            listener: None,
+            priority: None,
+            metrics: Arc::new(WorkerMetrics::default()),
+            shutdown: CancellationToken::new(),
+            cursor: None,
+            last_full_sweep: None,
+            idle_polls: 0,
         }
     }
 
-    /// Polls the database for new transactions
-    async fn poll_once(&mut self) -> AppResult<()> {
+    /// Wakes the worker on new transaction notifications
+    pub fn with_listener(mut self, listener: Box<dyn TransactionListener + Send + Sync>) -> Self {
+        self.listener = Some(listener);
+        self
+    }
+
+    /// Reports queue depth and in-flight counts into shared metrics
+    pub fn with_metrics(mut self, metrics: Arc<WorkerMetrics>) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Claims every batch as weighted shares of the priority lanes
+    pub fn with_priorities(mut self, priority: PriorityConfig) -> Self {
+        self.priority = Some(priority);
+        self
+    }
+
+    /// Stops claiming once `shutdown` is cancelled. Rows already being
+    /// processed finish, claimed rows that have not started are released.
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Wait before the next poll: the base interval while rows arrive, then
+    /// doubling for every empty poll past `idle_polls_before_backoff`. With a
+    /// listener, notifications wake the worker early and the interval applies
+    /// to the fallback sweep, starting from `fallback_poll_interval_seconds`.
+    fn poll_interval(&self) -> Duration {
+        let base = match self.listener {
+            Some(_) => self.config.fallback_poll_interval_seconds,
+            None => self.config.poll_interval_seconds,
+        };
+        backoff_interval(
+            base,
+            self.config.max_poll_interval_seconds,
+            self.idle_polls,
+            self.config.idle_polls_before_backoff,
+        )
+    }
+
+    /// Extends the idle streak after an empty poll and ends it after one that found rows
+    fn record_poll(&mut self, claimed: usize) {
+        let previous = self.poll_interval();
+        self.idle_polls = match claimed {
+            0 => self.idle_polls.saturating_add(1),
+            _ => 0,
+        };
+
+        let poll_interval = self.poll_interval();
+        if poll_interval > previous {
+            debug!("{} empty polls in a row, backing off to {}s", self.idle_polls, poll_interval.as_secs());
+        } else if poll_interval < previous {
+            info!("New records arrived, polling every {}s again", poll_interval.as_secs());
+        }
+        self.metrics.polled(self.idle_polls, poll_interval);
+    }
+
+    /// Waits for a notification or the fallback sweep, whichever comes first
+    async fn wait_for_work(&mut self) {
+        let poll_interval = self.poll_interval();
+        let Some(listener) = self.listener.as_mut() else {
+            sleep_or_shutdown(&self.shutdown, poll_interval).await;
+            return;
+        };
+
+        let fallback = poll_interval;
+        tokio::select! {
+            result = listener.wait_for_notification() => {
+                if let Err(e) = result {
+                    error!("Error waiting for notifications: {}", e);
+                    sleep_or_shutdown(&self.shutdown, Duration::from_secs(self.config.poll_interval_seconds)).await;
+                }
+            }
+            _ = tokio::time::sleep(fallback) => {
+                debug!("No notification within {}s, running fallback sweep", fallback.as_secs());
+            }
+            _ = self.shutdown.cancelled() => {}
+        }
+    }
+
+    /// Claims and processes batches of transactions above the cursor until
+    /// caught up, returning how many rows were claimed
+    async fn poll_once(&mut self) -> AppResult<usize> {
         info!("Polling for new records...");
 
-        let transactions = self
-            .transaction_repository
-            .fetch_new_transactions(self.last_checked)
-            .await?;
+        let full_sweep = full_sweep_due(self.last_full_sweep, self.config.full_sweep_interval_seconds);
+        let mut after_id = match (full_sweep, self.cursor) {
+            (true, _) => {
+                debug!("Sweeping all pending rows for inserts that committed behind the cursor");
+                self.last_full_sweep = Some(Instant::now());
+                0
+            }
+            (false, Some(last_id)) => last_id,
+            (false, None) => self.cursor_store.load_cursor(CURSOR_NAME).await?,
+        };
 
-        for transaction in transactions {
-            if let Err(e) = self.transaction_processor.process_transaction(&transaction).await {
-                error!("Error processing transaction {}: {}", transaction.id, e);
+        let mut claimed = 0;
+        while !self.shutdown.is_cancelled() {
+            let transactions = self.claim_batch(after_id).await?;
+            let caught_up = (transactions.len() as i64) < self.config.batch_size;
+            claimed += transactions.len();
+
+            self.process_claimed(transactions).await?;
+
+            let cursor = self.cursor_store.advance_cursor(CURSOR_NAME).await?;
+            self.cursor = Some(cursor);
+            // Processed rows are no longer pending, so a sweep can keep scanning from the start
+            if !full_sweep {
+                after_id = cursor;
+            }
+
+            if caught_up {
+                break;
+            }
+        }
+
+        Ok(claimed)
+    }
+
+    /// Claims up to `batch_size` rows above `after_id`, most urgent first.
+    /// Each lane first gets its weighted share, then room a lane could not use
+    /// goes to the remaining lanes in order of urgency.
+    async fn claim_batch(&self, after_id: i64) -> AppResult<Vec<Transaction>> {
+        let Some(priority) = &self.priority else {
+            return self.claim(after_id, None, self.config.batch_size).await;
+        };
+
+        let total_weight: i64 = TransactionPriority::DESCENDING
+            .iter()
+            .map(|&lane| priority.lane(lane).weight.max(0))
+            .sum();
+        let mut claimed: Vec<Vec<Transaction>> = Vec::new();
+        let mut exhausted = Vec::new();
+        let mut room = self.config.batch_size;
+
+        for lane in TransactionPriority::DESCENDING {
+            let weight = priority.lane(lane).weight.max(0);
+            let share = match total_weight {
+                0 => 0,
+                _ => (self.config.batch_size * weight / total_weight).max(1).min(room),
+            };
+
+            let transactions = match share {
+                0 => Vec::new(),
+                _ => self.claim(after_id, Some(lane), share).await?,
+            };
+            room -= transactions.len() as i64;
+            exhausted.push(share > 0 && (transactions.len() as i64) < share);
+            claimed.push(transactions);
+        }
+
+        for (index, lane) in TransactionPriority::DESCENDING.into_iter().enumerate() {
+            if room == 0 {
+                break;
+            }
+            if exhausted[index] {
+                continue;
+            }
+
+            // Rows of this lane leased in the first pass are still pending, so
+            // claim past them rather than leasing them a second time
+            let lane_after_id = claimed[index].last().map_or(after_id, |last| last.id as i64);
+            let transactions = self.claim(lane_after_id, Some(lane), room).await?;
+            room -= transactions.len() as i64;
+            claimed[index].extend(transactions);
+        }
+
+        let transactions: Vec<Transaction> = claimed.into_iter().flatten().collect();
+        if !transactions.is_empty() {
+            debug!(
+                "Claimed {} rows ({} urgent, {} high, {} routine)",
+                transactions.len(),
+                count_in(&transactions, TransactionPriority::Urgent),
+                count_in(&transactions, TransactionPriority::High),
+                count_in(&transactions, TransactionPriority::Routine)
+            );
+        }
+
+        Ok(transactions)
+    }
+
+    async fn claim(
+        &self,
+        after_id: i64,
+        priority: Option<TransactionPriority>,
+        limit: i64,
+    ) -> AppResult<Vec<Transaction>> {
+        self.transaction_repository
+            .claim_new_transactions(
+                &self.config.instance_id,
+                after_id,
+                priority,
+                limit,
+                self.config.lease_seconds,
+            )
+            .await
+    }
+
+    /// Processes claimed rows with up to `concurrency` senders at a time. Rows
+    /// of the same `from` address form a lane that runs strictly in claim
+    /// order. Senders whose first row is the most urgent get a permit first.
+    async fn process_claimed(&self, transactions: Vec<Transaction>) -> AppResult<()> {
+        let semaphore = Arc::new(Semaphore::new(self.config.concurrency.max(1)));
+        let mut lanes = JoinSet::new();
+
+        for transactions in lanes_by_sender(transactions) {
+            self.metrics.queued(transactions.len());
+
+            let lane = Lane {
+                transaction_repository: self.transaction_repository.clone(),
+                transaction_processor: self.transaction_processor.clone(),
+                metrics: self.metrics.clone(),
+                shutdown: self.shutdown.clone(),
+                owner: self.config.instance_id.clone(),
+            };
+            // Taking the permit before spawning hands them out in claim order
+            let permit = semaphore
+                .clone()
+                .acquire_owned()
+                .await
+                .map_err(|e| AppError::Internal(format!("Lane semaphore closed: {}", e)))?;
+
+            lanes.spawn(async move {
+                let _permit = permit;
+                lane.run(transactions).await
+            });
+        }
+
+        let mut result = Ok(());
+        while let Some(joined) = lanes.join_next().await {
+            match joined {
+                Ok(Ok(())) => {}
+                Ok(Err(e)) => {
+                    if result.is_ok() {
+                        result = Err(e);
+                    }
+                }
+                Err(e) => error!("Processing lane panicked: {}", e),
+            }
+        }
+
+        result
+    }
+}
+
+/// Whether the next claim should ignore the cursor and scan every pending row
+pub(crate) fn full_sweep_due(last_full_sweep: Option<Instant>, interval_seconds: u64) -> bool {
+    last_full_sweep.is_none_or(|at| at.elapsed() >= Duration::from_secs(interval_seconds))
+}
+
+fn count_in(transactions: &[Transaction], priority: TransactionPriority) -> usize {
+    transactions.iter().filter(|transaction| transaction.priority == priority).count()
+}
+
+/// `base_seconds`, doubled for every one of `idle_polls` from the
+/// `idle_polls_before_backoff`-th on, and capped at `max_seconds`
+fn backoff_interval(base_seconds: u64, max_seconds: u64, idle_polls: u32, idle_polls_before_backoff: u32) -> Duration {
+    let max = max_seconds.max(base_seconds);
+    let doublings = idle_polls.saturating_sub(idle_polls_before_backoff.saturating_sub(1));
+
+    let seconds = 2u64
+        .checked_pow(doublings)
+        .and_then(|factor| base_seconds.checked_mul(factor))
+        .map_or(max, |seconds| seconds.min(max));
+    Duration::from_secs(seconds)
+}
+
+/// Splits claimed rows into one lane per sender, keeping claim order within
+/// each lane, so a sender's urgent rows run before its routine ones
+fn lanes_by_sender(transactions: Vec<Transaction>) -> Vec<Vec<Transaction>> {
+    let mut lanes: Vec<Vec<Transaction>> = Vec::new();
+    let mut lane_of_sender: HashMap<String, usize> = HashMap::new();
+
+    for transaction in transactions {
+        // Rows without a sender fail validation anyway, so they get a lane of their own
+        let sender = transaction
+            .payload
+            .get("from")
+            .and_then(|from| from.as_str())
+            .map(|from| from.to_ascii_lowercase())
+            .unwrap_or_else(|| format!("#{}", transaction.id));
+
+        match lane_of_sender.get(&sender) {
+            Some(&index) => lanes[index].push(transaction),
+            None => {
+                lane_of_sender.insert(sender, lanes.len());
+                lanes.push(vec![transaction]);
+            }
+        }
+    }
+
+    lanes
+}
+
+/// Processes the rows of a single sender one after another
+struct Lane {
+    transaction_repository: Arc<dyn TransactionRepository + Send + Sync>,
+    transaction_processor: Arc<dyn TransactionProcessor + Send + Sync>,
+    metrics: Arc<WorkerMetrics>,
+    shutdown: CancellationToken,
+    owner: String,
+}
+
+impl Lane {
+    /// Hands the rows to the processor in claim order. On a database error the
+    /// row and everything after it is released so any replica can retry it.
+    async fn run(&self, transactions: Vec<Transaction>) -> AppResult<()> {
+        for (index, transaction) in transactions.iter().enumerate() {
+            if self.shutdown.is_cancelled() {
+                self.release(&transactions[index..]).await;
+                self.metrics.dropped(transactions.len() - index);
+                return Ok(());
+            }
+
+            self.metrics.started();
+            let result = self.transaction_processor.process_transaction(transaction).await;
+            self.metrics.finished();
+
+            match result {
+                Ok(()) => {}
+                Err(e @ AppError::Database(_)) => {
+                    self.release(&transactions[index..]).await;
+                    self.metrics.dropped(transactions.len() - index - 1);
+                    return Err(e);
+                }
+                Err(e) => error!("Error processing transaction {}: {}", transaction.id, e),
+            }
+
+            if let Err(e) = self
+                .transaction_repository
+                .complete_transaction(transaction.id, &self.owner)
+                .await
+            {
+                self.release(&transactions[index + 1..]).await;
+                self.metrics.dropped(transactions.len() - index - 1);
+                return Err(e);
             }
         }
 
-        self.last_checked = chrono::Utc::now();
         Ok(())
     }
+
+    async fn release(&self, transactions: &[Transaction]) {
+        for transaction in transactions {
+            if let Err(e) = self
+                .transaction_repository
+                .release_transaction(transaction.id, &self.owner)
+                .await
+            {
+                warn!("Could not release lease on transaction {}: {}", transaction.id, e);
+            }
+        }
+    }
 }
 
 #[async_trait::async_trait]
 impl AppService for PollingWorker {
     async fn start(&mut self) -> AppResult<()> {
         info!("Starting polling worker...");
-        self.running = true;
+        self.metrics.polled(self.idle_polls, self.poll_interval());
 
-        while self.running {
-            if let Err(e) = self.poll_once().await {
-                error!("Error during polling: {}", e);
+        while !self.shutdown.is_cancelled() {
+            // A failed poll neither extends nor ends the idle streak
+            match self.poll_once().await {
+                Ok(claimed) => self.record_poll(claimed),
+                Err(e) => error!("Error during polling: {}", e),
             }
 
-            tokio::time::sleep(Duration::from_secs(self.config.poll_interval_seconds)).await;
+            self.wait_for_work().await;
         }
 
+        info!("Polling worker drained");
         Ok(())
     }
 
     async fn stop(&self) -> AppResult<()> {
         info!("Stopping polling worker...");
-        // Note: In a real implementation, we'd need to handle graceful shutdown
-        // For now, we'll rely on the running flag being set to false externally
+        self.shutdown.cancel();
         Ok(())
     }
 }
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::domain::models::transaction::TransactionStatus;
+
+    fn transaction(id: i32, from: Option<&str>) -> Transaction {
+        Transaction {
+            id,
+            created_at: chrono::Utc::now(),
+            payload: match from {
+                Some(from) => serde_json::json!({ "from": from, "to": "0x456", "amount": "1" }),
+                None => serde_json::json!({ "to": "0x456", "amount": "1" }),
+            },
+            status: TransactionStatus::Pending,
+            priority: TransactionPriority::Routine,
+            execute_after: None,
+        }
+    }
+
+    fn ids(lanes: &[Vec<Transaction>]) -> Vec<Vec<i32>> {
+        lanes
+            .iter()
+            .map(|lane| lane.iter().map(|transaction| transaction.id).collect())
+            .collect()
+    }
+
+    #[test]
+    fn lanes_by_sender_keeps_claim_order_within_each_sender() {
+        let lanes = lanes_by_sender(vec![
+            transaction(1, Some("0xAAA")),
+            transaction(2, Some("0xbbb")),
+            transaction(3, Some("0xaaa")),
+            transaction(4, Some("0xbbb")),
+        ]);
+
+        assert_eq!(ids(&lanes), vec![vec![1, 3], vec![2, 4]]);
+    }
+
+    #[test]
+    fn lanes_by_sender_gives_rows_without_sender_their_own_lane() {
+        let lanes = lanes_by_sender(vec![
+            transaction(1, None),
+            transaction(2, Some("0xaaa")),
+            transaction(3, None),
+        ]);
+
+        assert_eq!(ids(&lanes), vec![vec![1], vec![2], vec![3]]);
+    }
+
+    #[test]
+    fn backoff_interval_holds_the_base_until_backoff_starts() {
+        assert_eq!(backoff_interval(1, 60, 0, 3), Duration::from_secs(1));
+        assert_eq!(backoff_interval(1, 60, 2, 3), Duration::from_secs(1));
+        assert_eq!(backoff_interval(1, 60, 3, 3), Duration::from_secs(2));
+        assert_eq!(backoff_interval(1, 60, 4, 3), Duration::from_secs(4));
+    }
+
+    #[test]
+    fn backoff_interval_is_capped() {
+        assert_eq!(backoff_interval(1, 60, 10, 1), Duration::from_secs(60));
+        assert_eq!(backoff_interval(5, 60, u32::MAX, 1), Duration::from_secs(60));
+    }
+
+    #[test]
+    fn backoff_interval_never_drops_below_the_base() {
+        assert_eq!(backoff_interval(90, 60, 0, 1), Duration::from_secs(90));
+        assert_eq!(backoff_interval(90, 60, 5, 1), Duration::from_secs(90));
+    }
+}
@@ -0,0 +1,100 @@
+use crate::{
+    config::RetryConfig,
+    error::AppResult,
+    shared::{
+        shutdown::sleep_or_shutdown,
+        traits::{AppService, ProcessedJobsTracker, TransactionProcessor, TransactionRepository},
+    },
+};
+use std::{sync::Arc, time::Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// A worker that re-runs failed jobs once their backoff has elapsed
+pub struct RetryWorker {
+    config: RetryConfig,
+    transaction_repository: Arc<dyn TransactionRepository + Send + Sync>,
+    processed_jobs_tracker: Arc<dyn ProcessedJobsTracker + Send + Sync>,
+    transaction_processor: Arc<dyn TransactionProcessor + Send + Sync>,
+    shutdown: CancellationToken,
+}
+
+impl RetryWorker {
+    /// Creates a new retry worker
+    pub fn new(
+        config: RetryConfig,
+        transaction_repository: Arc<dyn TransactionRepository + Send + Sync>,
+        processed_jobs_tracker: Arc<dyn ProcessedJobsTracker + Send + Sync>,
+        transaction_processor: Arc<dyn TransactionProcessor + Send + Sync>,
+    ) -> Self {
+        Self {
+            config,
+            transaction_repository,
+            processed_jobs_tracker,
+            transaction_processor,
+            shutdown: CancellationToken::new(),
+        }
+    }
+
+    /// Stops the worker between passes once `shutdown` is cancelled
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Retries every failed job that is due
+    async fn retry_once(&self) -> AppResult<()> {
+        let record_ids = self
+            .processed_jobs_tracker
+            .fetch_retryable_jobs(self.config.batch_size)
+            .await?;
+
+        if record_ids.is_empty() {
+            return Ok(());
+        }
+
+        info!("Retrying {} failed jobs...", record_ids.len());
+
+        for record_id in record_ids {
+            let Some(transaction) = self.transaction_repository.find_transaction(record_id as i32).await? else {
+                warn!("Record {} no longer exists, cannot retry it", record_id);
+                self.processed_jobs_tracker
+                    .dead_letter(record_id, "record no longer exists")
+                    .await?;
+                continue;
+            };
+
+            if let Err(e) = self.transaction_processor.retry_transaction(&transaction).await {
+                error!("Retry of record {} failed: {}", record_id, e);
+            }
+        }
+
+        Ok(())
+    }
+}
+
+#[async_trait::async_trait]
+impl AppService for RetryWorker {
+    async fn start(&mut self) -> AppResult<()> {
+        info!(
+            "Starting retry worker (max attempts: {})...",
+            self.config.max_attempts
+        );
+
+        loop {
+            if let Err(e) = self.retry_once().await {
+                error!("Error during retry sweep: {}", e);
+            }
+
+            if !sleep_or_shutdown(&self.shutdown, Duration::from_secs(self.config.poll_interval_seconds)).await {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn stop(&self) -> AppResult<()> {
+        info!("Stopping retry worker...");
+        self.shutdown.cancel();
+        Ok(())
+    }
+}
@@ -0,0 +1,283 @@
+use crate::{
+    config::StaleJobConfig,
+    domain::models::{
+        broadcast::SentTransaction,
+        leadership::FencingToken,
+        processed_job::{decode_hash, StaleJob},
+    },
+    error::AppResult,
+    shared::{
+        shutdown::sleep_or_shutdown,
+        traits::{AppService, BlockchainService, Fenced, ProcessedJobsTracker},
+    },
+};
+use std::{sync::Arc, time::Duration};
+use tokio_util::sync::CancellationToken;
+use tracing::{error, info, warn};
+
+/// A worker that recovers jobs whose worker died before they were broadcast.
+/// Jobs that were never signed are requeued. Signed jobs are rebroadcast with
+/// the stored bytes, and only requeued once the chain proves their transaction
+/// can no longer be mined, so a retry never pays twice.
+pub struct StaleJobWorker {
+    config: StaleJobConfig,
+    processed_jobs_tracker: Arc<dyn ProcessedJobsTracker + Send + Sync>,
+    blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+    shutdown: CancellationToken,
+    fencing_token: Option<FencingToken>,
+}
+
+impl StaleJobWorker {
+    /// Creates a new stale job worker
+    pub fn new(
+        config: StaleJobConfig,
+        processed_jobs_tracker: Arc<dyn ProcessedJobsTracker + Send + Sync>,
+        blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+    ) -> Self {
+        Self {
+            config,
+            processed_jobs_tracker,
+            blockchain_service,
+            shutdown: CancellationToken::new(),
+            fencing_token: None,
+        }
+    }
+
+    /// Stops the worker between passes once `shutdown` is cancelled
+    pub fn with_shutdown(mut self, shutdown: CancellationToken) -> Self {
+        self.shutdown = shutdown;
+        self
+    }
+
+    /// Recovers every job that has been stuck for longer than the stale timeout
+    async fn sweep_once(&self) -> AppResult<()> {
+        let updated_before = chrono::Utc::now() - chrono::Duration::seconds(self.config.stale_after_seconds);
+        let stale_jobs = self
+            .processed_jobs_tracker
+            .fetch_stale_jobs(updated_before, self.config.batch_size)
+            .await?;
+
+        if stale_jobs.is_empty() {
+            return Ok(());
+        }
+
+        info!("Recovering {} stale jobs...", stale_jobs.len());
+
+        for job in stale_jobs {
+            if let Err(e) = self.recover(&job).await {
+                error!("Error recovering stale record {}: {}", job.record_id, e);
+            }
+        }
+
+        Ok(())
+    }
+
+    async fn recover(&self, job: &StaleJob) -> AppResult<()> {
+        // Broadcasting only happens after the signed transition, so earlier states never reached the chain
+        let (Some(tx_hash), Some(nonce)) = (&job.tx_hash, job.nonce) else {
+            self.processed_jobs_tracker
+                .requeue_stale(
+                    job,
+                    &format!("worker stopped while the job was {}", job.status),
+                    self.fencing_token.as_ref(),
+                )
+                .await?;
+            return Ok(());
+        };
+        let tx_hash_bytes = decode_hash(tx_hash)?;
+        let nonce = nonce as u64;
+
+        if let Some(published) = self.blockchain_service.get_transaction(tx_hash_bytes).await? {
+            info!("Stale record {} was broadcast as {} after all", job.record_id, tx_hash);
+            self.processed_jobs_tracker
+                .mark_broadcast(job.record_id, &SentTransaction::from(&published))
+                .await?;
+            return Ok(());
+        }
+
+        if self.blockchain_service.mined_nonce().await? <= nonce {
+            return self.rebroadcast(job, nonce).await;
+        }
+
+        // The nonce is used up; look once more in case the transaction was mined meanwhile
+        if self.blockchain_service.get_transaction(tx_hash_bytes).await?.is_some() {
+            return Ok(());
+        }
+
+        self.processed_jobs_tracker
+            .requeue_stale(
+                job,
+                &format!("transaction {} was never mined and nonce {} was used by another", tx_hash, nonce),
+                self.fencing_token.as_ref(),
+            )
+            .await?;
+        Ok(())
+    }
+}
+
+impl StaleJobWorker {
+    /// Rebroadcasts the bytes written ahead of the original broadcast. Only if
+    /// the node refuses them for good is the nonce given up, so the nonce worker
+    /// closes it and the job is requeued on a later sweep; transient errors are
+    /// retried on the next sweep, as the bytes may still be mined.
+    async fn rebroadcast(&self, job: &StaleJob, nonce: u64) -> AppResult<()> {
+        let Some(signed) = job.signed_transaction()? else {
+            warn!("Stale record {} has no signed bytes to rebroadcast; releasing nonce {}", job.record_id, nonce);
+            return self.blockchain_service.release_nonce(nonce).await;
+        };
+
+        match self.blockchain_service.broadcast_transaction(&signed).await {
+            Ok(sent) => {
+                info!("Rebroadcast {} for stale record {}", signed.tx_hash_hex(), job.record_id);
+                self.processed_jobs_tracker.mark_broadcast(job.record_id, &sent).await?;
+                Ok(())
+            }
+            Err(e) if e.is_retryable() => {
+                warn!(
+                    "Could not rebroadcast {} for stale record {}, retrying on the next sweep: {}",
+                    signed.tx_hash_hex(),
+                    job.record_id,
+                    e
+                );
+                Err(e)
+            }
+            Err(e) => {
+                warn!(
+                    "Could not rebroadcast {} for stale record {}, releasing nonce {}: {}",
+                    signed.tx_hash_hex(),
+                    job.record_id,
+                    nonce,
+                    e
+                );
+                self.blockchain_service.release_nonce(nonce).await
+            }
+        }
+    }
+}
+
+impl Fenced for StaleJobWorker {
+    fn set_fencing_token(&mut self, token: FencingToken) {
+        self.fencing_token = Some(token);
+    }
+}
+
+#[async_trait::async_trait]
+impl AppService for StaleJobWorker {
+    async fn start(&mut self) -> AppResult<()> {
+        info!(
+            "Starting stale job worker (stale after {}s)...",
+            self.config.stale_after_seconds
+        );
+
+        loop {
+            if let Err(e) = self.sweep_once().await {
+                error!("Error during stale job sweep: {}", e);
+            }
+
+            if !sleep_or_shutdown(&self.shutdown, Duration::from_secs(self.config.poll_interval_seconds)).await {
+                return Ok(());
+            }
+        }
+    }
+
+    async fn stop(&self) -> AppResult<()> {
+        info!("Stopping stale job worker...");
+        self.shutdown.cancel();
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{
+        domain::models::{broadcast::SignedTransaction, processed_job::JobStatus},
+        infrastructure::{
+            blockchain::simulated::SimulatedBlockchainClient,
+            database::{connection::test_pool, repositories::processed_jobs_repo::ProcessedJobsTracker as PostgresTracker},
+        },
+    };
+
+    fn signed(byte: u8, nonce: u64) -> SignedTransaction {
+        SignedTransaction {
+            tx_hash: [byte; 32],
+            nonce,
+            max_fee_per_gas: 100,
+            max_priority_fee_per_gas: 2,
+            raw: vec![byte],
+        }
+    }
+
+    fn worker(tracker: Arc<PostgresTracker>) -> StaleJobWorker {
+        let config = StaleJobConfig {
+            poll_interval_seconds: 1,
+            stale_after_seconds: 0,
+            batch_size: 10,
+        };
+        StaleJobWorker::new(config, tracker, Arc::new(SimulatedBlockchainClient::new()))
+    }
+
+    async fn status(tracker: &PostgresTracker, record_id: i64) -> JobStatus {
+        tracker.get_status(record_id).await.unwrap().unwrap().0
+    }
+
+    #[tokio::test]
+    async fn sweep_requeues_unsigned_jobs_and_rebroadcasts_signed_ones() {
+        let tracker = Arc::new(PostgresTracker::new(test_pool("stale_sweep").await));
+        tracker.claim(1).await.unwrap();
+        tracker.claim(2).await.unwrap();
+        tracker.mark_validated(2).await.unwrap();
+        for record_id in [3, 4] {
+            tracker.claim(record_id).await.unwrap();
+            tracker.mark_validated(record_id).await.unwrap();
+            tracker.mark_signed(record_id, &signed(record_id as u8, record_id as u64)).await.unwrap();
+        }
+        tracker.mark_broadcast(4, &signed(4, 4).sent()).await.unwrap();
+
+        worker(tracker.clone()).sweep_once().await.unwrap();
+
+        assert_eq!(status(&tracker, 1).await, JobStatus::Failed);
+        assert_eq!(status(&tracker, 2).await, JobStatus::Failed);
+        assert_eq!(
+            tracker.get_status(3).await.unwrap(),
+            Some((JobStatus::Broadcast, Some(signed(3, 3).tx_hash_hex())))
+        );
+        assert_eq!(status(&tracker, 4).await, JobStatus::Broadcast);
+        let mut retryable = tracker.fetch_retryable_jobs(10).await.unwrap();
+        retryable.sort();
+        assert_eq!(retryable, vec![1, 2]);
+    }
+
+    #[tokio::test]
+    async fn requeue_leaves_jobs_that_progressed_since_they_were_fetched() {
+        let tracker = PostgresTracker::new(test_pool("stale_progressed").await);
+        tracker.claim(1).await.unwrap();
+        let stale_jobs = tracker.fetch_stale_jobs(chrono::Utc::now(), 10).await.unwrap();
+        assert_eq!(stale_jobs.len(), 1);
+
+        tracker.mark_validated(1).await.unwrap();
+
+        assert!(!tracker.requeue_stale(&stale_jobs[0], "worker stopped", None).await.unwrap());
+        assert_eq!(status(&tracker, 1).await, JobStatus::Validated);
+    }
+
+    #[tokio::test]
+    async fn requeue_is_refused_to_a_superseded_leader() {
+        let pool = test_pool("stale_fenced").await;
+        sqlx::query("INSERT INTO leader_tokens (role, token) VALUES ('stale_job_worker', 2)")
+            .execute(&pool)
+            .await
+            .unwrap();
+        let tracker = PostgresTracker::new(pool);
+        tracker.claim(1).await.unwrap();
+        let stale_jobs = tracker.fetch_stale_jobs(chrono::Utc::now(), 10).await.unwrap();
+        let fence = |token| FencingToken {
+            role: "stale_job_worker".to_string(),
+            token,
+        };
+
+        assert!(!tracker.requeue_stale(&stale_jobs[0], "worker stopped", Some(&fence(1))).await.unwrap());
+        assert!(tracker.requeue_stale(&stale_jobs[0], "worker stopped", Some(&fence(2))).await.unwrap());
+        assert_eq!(status(&tracker, 1).await, JobStatus::Failed);
+    }
+}
@@ -1,6 +1,7 @@
 use anyhow::{Context, Result};
 use serde::Deserialize;
-use std::env;
+use crate::domain::models::transaction::{PriorityFee, TransactionPriority};
+use std::{env, str::FromStr};
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct Config {
@@ -9,6 +10,12 @@ pub struct Config {
     pub blockchain: BlockchainConfig,
     pub server: ServerConfig,
     pub worker: WorkerConfig,
+    pub anchoring: AnchoringConfig,
+    pub confirmation: ConfirmationConfig,
+    pub fee_bump: FeeBumpConfig,
+    pub retry: RetryConfig,
+    pub stale_jobs: StaleJobConfig,
+    pub priority: PriorityConfig,
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -20,13 +27,50 @@ pub struct DatabaseConfig {
 #[derive(Debug, Clone, Deserialize)]
 pub struct RedisConfig {
     pub url: String,
+    /// TTL of the leader lock held by replicas running singleton workers
+    pub leader_lease_ms: u64,
 }
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct BlockchainConfig {
+    pub mode: BlockchainMode,
     pub rpc_url: String,
     pub private_key: String,
     pub chain_id: u64,
+    pub nonce_gap_check_interval_seconds: u64,
+    /// How long an unsigned nonce reservation is protected from a resync
+    pub nonce_reservation_grace_seconds: u64,
+}
+
+/// Which `BlockchainService` implementation the service runs with
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum BlockchainMode {
+    /// Sign with `PRIVATE_KEY` and broadcast through `RPC_URL`
+    Rpc,
+    /// Log transfers and return zeroed hashes without touching the network
+    Simulated,
+}
+
+impl BlockchainMode {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            BlockchainMode::Rpc => "rpc",
+            BlockchainMode::Simulated => "simulated",
+        }
+    }
+}
+
+impl FromStr for BlockchainMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "rpc" => Ok(BlockchainMode::Rpc),
+            "simulated" => Ok(BlockchainMode::Simulated),
+            other => anyhow::bail!("unknown blockchain mode '{}'", other),
+        }
+    }
 }
 
 #[derive(Debug, Clone, Deserialize)]
@@ -37,8 +81,147 @@ pub struct ServerConfig {
 
 #[derive(Debug, Clone, Deserialize)]
 pub struct WorkerConfig {
+    pub mode: WorkerMode,
+    /// Interval between polls while rows keep arriving
     pub poll_interval_seconds: u64,
-    pub lookback_hours: i64,
+    /// Ceiling the interval doubles toward while polls come back empty
+    pub max_poll_interval_seconds: u64,
+    /// Empty polls in a row before the interval starts to grow
+    pub idle_polls_before_backoff: u32,
+    pub listen_enabled: bool,
+    /// Interval of the sweep that backs up notifications, which backs off like `poll_interval_seconds`
+    pub fallback_poll_interval_seconds: u64,
+    pub batch_size: i64,
+    /// Identifies this replica as the owner of claimed rows
+    pub instance_id: String,
+    pub lease_seconds: i64,
+    /// How often a claim starts from the first row instead of the cursor, to
+    /// pick up rows whose insert committed after the cursor passed their id
+    pub full_sweep_interval_seconds: u64,
+    /// How many senders are processed at once; each sender's rows stay in order
+    pub concurrency: usize,
+    /// How long in-flight jobs may take to finish on shutdown
+    pub drain_timeout_seconds: u64,
+}
+
+/// What the worker publishes for each `transactions` row
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum WorkerMode {
+    /// One transfer or contract call per row
+    Transfer,
+    /// One Merkle root per batch of rows
+    Anchoring,
+}
+
+impl FromStr for WorkerMode {
+    type Err = anyhow::Error;
+
+    fn from_str(s: &str) -> Result<Self> {
+        match s.to_ascii_lowercase().as_str() {
+            "transfer" => Ok(WorkerMode::Transfer),
+            "anchoring" => Ok(WorkerMode::Anchoring),
+            other => anyhow::bail!("unknown worker mode '{}'", other),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct AnchoringConfig {
+    pub batch_size: usize,
+    pub max_wait_seconds: u64,
+    pub contract: Option<String>,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct ConfirmationConfig {
+    pub poll_interval_seconds: u64,
+    pub required_confirmations: u64,
+    pub reorg_depth: u64,
+    pub batch_size: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct FeeBumpConfig {
+    pub poll_interval_seconds: u64,
+    pub stuck_after_seconds: i64,
+    pub bump_percent: u64,
+    pub max_fee_per_gas_wei: u128,
+    pub batch_size: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct RetryConfig {
+    pub poll_interval_seconds: u64,
+    pub max_attempts: i32,
+    pub base_delay_seconds: u64,
+    pub max_delay_seconds: u64,
+    pub jitter_percent: u32,
+    pub batch_size: i64,
+}
+
+#[derive(Debug, Clone, Deserialize)]
+pub struct StaleJobConfig {
+    pub poll_interval_seconds: u64,
+    /// Must comfortably exceed `ANCHOR_MAX_WAIT_SECONDS`, since buffered anchoring records stay validated
+    pub stale_after_seconds: i64,
+    pub batch_size: i64,
+}
+
+/// How much of each claim a priority lane gets and what it pays to be mined
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct LaneConfig {
+    /// Share of every batch reserved for this lane, relative to the other lanes
+    pub weight: i64,
+    /// Tip for this lane's transactions: a fixed one when `LANE_*_PRIORITY_FEE_WEI`
+    /// is set, otherwise `LANE_*_PRIORITY_FEE_PERCENT` of the node's estimate
+    pub priority_fee: PriorityFee,
+}
+
+impl LaneConfig {
+    fn from_env(lane: &str, default_weight: &str, default_fee_percent: &str) -> Result<Self> {
+        let weight_var = format!("LANE_{}_WEIGHT", lane);
+        let fee_var = format!("LANE_{}_PRIORITY_FEE_WEI", lane);
+        let fee_percent_var = format!("LANE_{}_PRIORITY_FEE_PERCENT", lane);
+
+        let priority_fee = match env::var(&fee_var) {
+            Ok(fee) => PriorityFee::Fixed(
+                fee.parse()
+                    .with_context(|| format!("{} must be a valid number", fee_var))?,
+            ),
+            Err(_) => PriorityFee::PercentOfEstimate(
+                env::var(&fee_percent_var)
+                    .unwrap_or_else(|_| default_fee_percent.to_string())
+                    .parse()
+                    .with_context(|| format!("{} must be a valid number", fee_percent_var))?,
+            ),
+        };
+
+        Ok(Self {
+            weight: env::var(&weight_var)
+                .unwrap_or_else(|_| default_weight.to_string())
+                .parse()
+                .with_context(|| format!("{} must be a valid number", weight_var))?,
+            priority_fee,
+        })
+    }
+}
+
+#[derive(Debug, Clone, Copy, Deserialize)]
+pub struct PriorityConfig {
+    pub routine: LaneConfig,
+    pub high: LaneConfig,
+    pub urgent: LaneConfig,
+}
+
+impl PriorityConfig {
+    pub fn lane(&self, priority: TransactionPriority) -> &LaneConfig {
+        match priority {
+            TransactionPriority::Routine => &self.routine,
+            TransactionPriority::High => &self.high,
+            TransactionPriority::Urgent => &self.urgent,
+        }
+    }
 }
 
 impl Config {
@@ -55,8 +238,16 @@ impl Config {
             redis: RedisConfig {
                 url: env::var("REDIS_URL")
                     .context("REDIS_URL environment variable is required")?,
+                leader_lease_ms: env::var("LEADER_LEASE_MS")
+                    .unwrap_or_else(|_| "15000".to_string())
+                    .parse()
+                    .context("LEADER_LEASE_MS must be a valid number")?,
             },
             blockchain: BlockchainConfig {
+                mode: env::var("BLOCKCHAIN_MODE")
+                    .unwrap_or_else(|_| "rpc".to_string())
+                    .parse()
+                    .context("BLOCKCHAIN_MODE must be either 'rpc' or 'simulated'")?,
                 rpc_url: env::var("RPC_URL")
                     .context("RPC_URL environment variable is required")?,
                 private_key: env::var("PRIVATE_KEY")
@@ -65,6 +256,14 @@ impl Config {
                     .unwrap_or_else(|_| "1".to_string())
                     .parse()
                     .context("CHAIN_ID must be a valid number")?,
+                nonce_gap_check_interval_seconds: env::var("NONCE_GAP_CHECK_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .context("NONCE_GAP_CHECK_INTERVAL_SECONDS must be a valid number")?,
+                nonce_reservation_grace_seconds: env::var("NONCE_RESERVATION_GRACE_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .context("NONCE_RESERVATION_GRACE_SECONDS must be a valid number")?,
             },
             server: ServerConfig {
                 host: env::var("SERVER_HOST").unwrap_or_else(|_| "0.0.0.0".to_string()),
@@ -74,15 +273,151 @@ impl Config {
                     .context("SERVER_PORT must be a valid number")?,
             },
             worker: WorkerConfig {
+                mode: env::var("WORKER_MODE")
+                    .unwrap_or_else(|_| "transfer".to_string())
+                    .parse()
+                    .context("WORKER_MODE must be either 'transfer' or 'anchoring'")?,
                 poll_interval_seconds: env::var("WORKER_POLL_INTERVAL_SECONDS")
                     .unwrap_or_else(|_| "5".to_string())
                     .parse()
                     .context("WORKER_POLL_INTERVAL_SECONDS must be a valid number")?,
-                lookback_hours: env::var("WORKER_LOOKBACK_HOURS")
-                    .unwrap_or_else(|_| "1".to_string())
+                max_poll_interval_seconds: env::var("WORKER_MAX_POLL_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .context("WORKER_MAX_POLL_INTERVAL_SECONDS must be a valid number")?,
+                idle_polls_before_backoff: env::var("WORKER_IDLE_POLLS_BEFORE_BACKOFF")
+                    .unwrap_or_else(|_| "3".to_string())
+                    .parse()
+                    .context("WORKER_IDLE_POLLS_BEFORE_BACKOFF must be a valid number")?,
+                listen_enabled: env::var("WORKER_LISTEN_ENABLED")
+                    .unwrap_or_else(|_| "true".to_string())
+                    .parse()
+                    .context("WORKER_LISTEN_ENABLED must be true or false")?,
+                fallback_poll_interval_seconds: env::var("WORKER_FALLBACK_POLL_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .context("WORKER_FALLBACK_POLL_INTERVAL_SECONDS must be a valid number")?,
+                batch_size: env::var("WORKER_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .context("WORKER_BATCH_SIZE must be a valid number")?,
+                instance_id: env::var("WORKER_INSTANCE_ID").unwrap_or_else(|_| {
+                    let host = env::var("HOSTNAME").unwrap_or_else(|_| "localhost".to_string());
+                    format!("{}-{}", host, std::process::id())
+                }),
+                lease_seconds: env::var("WORKER_LEASE_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .context("WORKER_LEASE_SECONDS must be a valid number")?,
+                full_sweep_interval_seconds: env::var("WORKER_FULL_SWEEP_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .context("WORKER_FULL_SWEEP_INTERVAL_SECONDS must be a valid number")?,
+                concurrency: env::var("WORKER_CONCURRENCY")
+                    .unwrap_or_else(|_| "8".to_string())
+                    .parse()
+                    .context("WORKER_CONCURRENCY must be a valid number")?,
+                drain_timeout_seconds: env::var("WORKER_DRAIN_TIMEOUT_SECONDS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .context("WORKER_DRAIN_TIMEOUT_SECONDS must be a valid number")?,
+            },
+            anchoring: AnchoringConfig {
+                batch_size: env::var("ANCHOR_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .context("ANCHOR_BATCH_SIZE must be a valid number")?,
+                max_wait_seconds: env::var("ANCHOR_MAX_WAIT_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .context("ANCHOR_MAX_WAIT_SECONDS must be a valid number")?,
+                contract: env::var("ANCHOR_CONTRACT").ok(),
+            },
+            confirmation: ConfirmationConfig {
+                poll_interval_seconds: env::var("CONFIRMATION_POLL_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "15".to_string())
                     .parse()
-                    .context("WORKER_LOOKBACK_HOURS must be a valid number")?,
+                    .context("CONFIRMATION_POLL_INTERVAL_SECONDS must be a valid number")?,
+                required_confirmations: env::var("CONFIRMATION_REQUIRED_BLOCKS")
+                    .unwrap_or_else(|_| "12".to_string())
+                    .parse()
+                    .context("CONFIRMATION_REQUIRED_BLOCKS must be a valid number")?,
+                reorg_depth: env::var("CONFIRMATION_REORG_DEPTH")
+                    .unwrap_or_else(|_| "64".to_string())
+                    .parse()
+                    .context("CONFIRMATION_REORG_DEPTH must be a valid number")?,
+                batch_size: env::var("CONFIRMATION_BATCH_SIZE")
+                    .unwrap_or_else(|_| "100".to_string())
+                    .parse()
+                    .context("CONFIRMATION_BATCH_SIZE must be a valid number")?,
+            },
+            fee_bump: FeeBumpConfig {
+                poll_interval_seconds: env::var("FEE_BUMP_POLL_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "30".to_string())
+                    .parse()
+                    .context("FEE_BUMP_POLL_INTERVAL_SECONDS must be a valid number")?,
+                stuck_after_seconds: env::var("FEE_BUMP_STUCK_AFTER_SECONDS")
+                    .unwrap_or_else(|_| "300".to_string())
+                    .parse()
+                    .context("FEE_BUMP_STUCK_AFTER_SECONDS must be a valid number")?,
+                bump_percent: env::var("FEE_BUMP_PERCENT")
+                    .unwrap_or_else(|_| "15".to_string())
+                    .parse()
+                    .context("FEE_BUMP_PERCENT must be a valid number")?,
+                max_fee_per_gas_wei: env::var("FEE_BUMP_MAX_FEE_PER_GAS_WEI")
+                    .unwrap_or_else(|_| "200000000000".to_string())
+                    .parse()
+                    .context("FEE_BUMP_MAX_FEE_PER_GAS_WEI must be a valid number")?,
+                batch_size: env::var("FEE_BUMP_BATCH_SIZE")
+                    .unwrap_or_else(|_| "50".to_string())
+                    .parse()
+                    .context("FEE_BUMP_BATCH_SIZE must be a valid number")?,
+            },
+            retry: RetryConfig {
+                poll_interval_seconds: env::var("RETRY_POLL_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .context("RETRY_POLL_INTERVAL_SECONDS must be a valid number")?,
+                max_attempts: env::var("RETRY_MAX_ATTEMPTS")
+                    .unwrap_or_else(|_| "5".to_string())
+                    .parse()
+                    .context("RETRY_MAX_ATTEMPTS must be a valid number")?,
+                base_delay_seconds: env::var("RETRY_BASE_DELAY_SECONDS")
+                    .unwrap_or_else(|_| "10".to_string())
+                    .parse()
+                    .context("RETRY_BASE_DELAY_SECONDS must be a valid number")?,
+                max_delay_seconds: env::var("RETRY_MAX_DELAY_SECONDS")
+                    .unwrap_or_else(|_| "600".to_string())
+                    .parse()
+                    .context("RETRY_MAX_DELAY_SECONDS must be a valid number")?,
+                jitter_percent: env::var("RETRY_JITTER_PERCENT")
+                    .unwrap_or_else(|_| "20".to_string())
+                    .parse()
+                    .context("RETRY_JITTER_PERCENT must be a valid number")?,
+                batch_size: env::var("RETRY_BATCH_SIZE")
+                    .unwrap_or_else(|_| "50".to_string())
+                    .parse()
+                    .context("RETRY_BATCH_SIZE must be a valid number")?,
+            },
+            stale_jobs: StaleJobConfig {
+                poll_interval_seconds: env::var("STALE_JOB_POLL_INTERVAL_SECONDS")
+                    .unwrap_or_else(|_| "60".to_string())
+                    .parse()
+                    .context("STALE_JOB_POLL_INTERVAL_SECONDS must be a valid number")?,
+                stale_after_seconds: env::var("STALE_JOB_AFTER_SECONDS")
+                    .unwrap_or_else(|_| "600".to_string())
+                    .parse()
+                    .context("STALE_JOB_AFTER_SECONDS must be a valid number")?,
+                batch_size: env::var("STALE_JOB_BATCH_SIZE")
+                    .unwrap_or_else(|_| "50".to_string())
+                    .parse()
+                    .context("STALE_JOB_BATCH_SIZE must be a valid number")?,
+            },
+            priority: PriorityConfig {
+                routine: LaneConfig::from_env("ROUTINE", "1", "100")?,
+                high: LaneConfig::from_env("HIGH", "3", "150")?,
+                urgent: LaneConfig::from_env("URGENT", "6", "200")?,
             },
         })
     }
-} 
\ No newline at end of file
+}
\ No newline at end of file
@@ -0,0 +1,16 @@
+/// A batch of records whose payload hashes were published as one Merkle root
+#[derive(Debug, Clone)]
+pub struct AnchorBatch {
+    pub merkle_root: [u8; 32],
+    pub tx_hash: String,
+    pub leaves: Vec<AnchorLeaf>,
+}
+
+/// One record of an anchor batch with the proof linking it to the root
+#[derive(Debug, Clone)]
+pub struct AnchorLeaf {
+    pub record_id: i64,
+    pub leaf_index: usize,
+    pub leaf_hash: [u8; 32],
+    pub proof: Vec<[u8; 32]>,
+}
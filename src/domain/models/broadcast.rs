@@ -0,0 +1,69 @@
+/// A transaction accepted by the node, with the nonce and fees it was signed with
+#[derive(Debug, Clone)]
+pub struct SentTransaction {
+    pub tx_hash: [u8; 32],
+    pub nonce: u64,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+}
+
+impl From<&PublishedTransaction> for SentTransaction {
+    fn from(published: &PublishedTransaction) -> Self {
+        Self {
+            tx_hash: published.tx_hash,
+            nonce: published.nonce,
+            max_fee_per_gas: published.max_fee_per_gas,
+            max_priority_fee_per_gas: published.max_priority_fee_per_gas,
+        }
+    }
+}
+
+impl SentTransaction {
+    /// The transaction hash as stored in `processed_jobs`
+    pub fn tx_hash_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.tx_hash))
+    }
+}
+
+/// A transaction signed with a reserved nonce but not yet handed to the node
+#[derive(Debug, Clone)]
+pub struct SignedTransaction {
+    pub tx_hash: [u8; 32],
+    pub nonce: u64,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    /// EIP-2718 encoded transaction, exactly as it will be broadcast
+    pub raw: Vec<u8>,
+}
+
+impl SignedTransaction {
+    /// The transaction hash as stored in `processed_jobs`
+    pub fn tx_hash_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.tx_hash))
+    }
+
+    /// The same transaction once the node has accepted it
+    pub fn sent(&self) -> SentTransaction {
+        SentTransaction {
+            tx_hash: self.tx_hash,
+            nonce: self.nonce,
+            max_fee_per_gas: self.max_fee_per_gas,
+            max_priority_fee_per_gas: self.max_priority_fee_per_gas,
+        }
+    }
+}
+
+/// A transaction as the chain reports it, used as evidence when verifying
+/// records and when recovering jobs that may or may not have been broadcast
+#[derive(Debug, Clone)]
+pub struct PublishedTransaction {
+    pub tx_hash: [u8; 32],
+    pub nonce: u64,
+    pub max_fee_per_gas: u128,
+    pub max_priority_fee_per_gas: u128,
+    pub input: Vec<u8>,
+    /// `None` while the transaction is still pending
+    pub block_number: Option<u64>,
+    /// Unix timestamp of the inclusion block
+    pub block_timestamp: Option<u64>,
+}
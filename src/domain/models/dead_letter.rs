@@ -0,0 +1,15 @@
+use serde::Serialize;
+
+/// A job parked after it ran out of retries or failed permanently
+#[derive(Debug, Clone, Serialize)]
+pub struct DeadLetter {
+    pub id: i64,
+    pub record_id: i64,
+    pub attempts: i32,
+    /// Every recorded failure of the job, oldest first
+    pub errors: serde_json::Value,
+    /// Snapshot of the source payload, `None` if the row was already gone
+    pub payload: Option<serde_json::Value>,
+    pub created_at: chrono::DateTime<chrono::Utc>,
+    pub replayed_at: Option<chrono::DateTime<chrono::Utc>>,
+}
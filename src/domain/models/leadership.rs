@@ -0,0 +1,8 @@
+/// Proof of leadership over a singleton worker role. Writes made as leader
+/// only apply while `token` is still the newest one registered for `role`, so
+/// a leader that stalled past its lease cannot overwrite its successor.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct FencingToken {
+    pub role: String,
+    pub token: i64,
+}
@@ -1 +1,7 @@
-pub mod transaction; 
\ No newline at end of file
+pub mod anchor;
+pub mod broadcast;
+pub mod dead_letter;
+pub mod leadership;
+pub mod processed_job;
+pub mod receipt;
+pub mod transaction;
\ No newline at end of file
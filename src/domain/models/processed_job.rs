@@ -0,0 +1,173 @@
+use crate::{
+    domain::models::broadcast::SignedTransaction,
+    error::{AppError, AppResult},
+};
+use serde::{Deserialize, Serialize};
+use std::fmt;
+
+/// State of a job in `processed_jobs`:
+/// claimed -> validated -> signed -> broadcast -> confirmed, or failed/cancelled/dead_lettered.
+/// A cancelled broadcast passes through cancelling until one of its transactions is mined.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "job_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Claimed,
+    Validated,
+    Signed,
+    Broadcast,
+    /// A cancellation was sent, but the original may still be the one mined
+    Cancelling,
+    Confirmed,
+    /// Waiting for a retry
+    Failed,
+    Cancelled,
+    DeadLettered,
+}
+
+impl JobStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            JobStatus::Claimed => "claimed",
+            JobStatus::Validated => "validated",
+            JobStatus::Signed => "signed",
+            JobStatus::Broadcast => "broadcast",
+            JobStatus::Cancelling => "cancelling",
+            JobStatus::Confirmed => "confirmed",
+            JobStatus::Failed => "failed",
+            JobStatus::Cancelled => "cancelled",
+            JobStatus::DeadLettered => "dead_lettered",
+        }
+    }
+}
+
+impl fmt::Display for JobStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A row of `processed_jobs` as reported by the API
+#[derive(Debug, Clone, Serialize)]
+pub struct ProcessedJob {
+    pub record_id: i64,
+    pub tx_hash: Option<String>,
+    pub status: JobStatus,
+    pub attempts: i32,
+    pub updated_at: Option<chrono::DateTime<chrono::Utc>>,
+}
+
+/// A job whose transaction, or its cancellation, has been broadcast but not yet mined
+#[derive(Debug, Clone)]
+pub struct SentJob {
+    pub record_id: i64,
+    /// Hash of the most recent broadcast
+    pub tx_hash: String,
+    /// Every hash broadcast for this job, newest first; any of them may get mined
+    pub tx_hashes: Vec<String>,
+    /// Those of `tx_hashes` that cancel the job when mined
+    pub cancellation_tx_hashes: Vec<String>,
+    pub nonce: Option<i64>,
+    /// Signed bytes of `tx_hash`, written ahead of its broadcast
+    pub raw_tx: Option<Vec<u8>>,
+    pub max_fee_per_gas: Option<i64>,
+    pub max_priority_fee_per_gas: Option<i64>,
+}
+
+impl SentJob {
+    /// Decodes the stored `0x`-prefixed transaction hash
+    pub fn tx_hash_bytes(&self) -> AppResult<[u8; 32]> {
+        decode_hash(&self.tx_hash)
+    }
+
+    /// Whether `tx_hash` is a cancellation rather than the job's own transaction
+    pub fn is_cancellation(&self, tx_hash: &str) -> bool {
+        self.cancellation_tx_hashes.iter().any(|hash| hash == tx_hash)
+    }
+
+    /// The most recent transaction exactly as it was signed, if its bytes were kept
+    pub fn signed_transaction(&self) -> AppResult<Option<SignedTransaction>> {
+        signed_transaction(
+            Some(&self.tx_hash),
+            self.nonce,
+            self.raw_tx.as_deref(),
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+        )
+    }
+}
+
+/// A confirmed or cancelled job together with the block its transaction was included in
+#[derive(Debug, Clone)]
+pub struct ConfirmedJob {
+    pub record_id: i64,
+    pub tx_hash: String,
+    pub block_number: i64,
+    pub block_hash: String,
+}
+
+impl ConfirmedJob {
+    /// Decodes the stored `0x`-prefixed block hash
+    pub fn block_hash_bytes(&self) -> AppResult<[u8; 32]> {
+        decode_hash(&self.block_hash)
+    }
+}
+
+/// A job that has not moved out of `claimed`, `validated` or `signed` for
+/// longer than the stale timeout, typically because its worker died
+#[derive(Debug, Clone)]
+pub struct StaleJob {
+    pub record_id: i64,
+    pub status: JobStatus,
+    pub updated_at: chrono::DateTime<chrono::Utc>,
+    /// Set once the job is signed
+    pub tx_hash: Option<String>,
+    /// Set once the job is signed
+    pub nonce: Option<i64>,
+    /// Signed bytes, written ahead of the broadcast
+    pub raw_tx: Option<Vec<u8>>,
+    pub max_fee_per_gas: Option<i64>,
+    pub max_priority_fee_per_gas: Option<i64>,
+}
+
+impl StaleJob {
+    /// The transaction exactly as it was signed, if the job got that far
+    pub fn signed_transaction(&self) -> AppResult<Option<SignedTransaction>> {
+        signed_transaction(
+            self.tx_hash.as_deref(),
+            self.nonce,
+            self.raw_tx.as_deref(),
+            self.max_fee_per_gas,
+            self.max_priority_fee_per_gas,
+        )
+    }
+}
+
+fn signed_transaction(
+    tx_hash: Option<&str>,
+    nonce: Option<i64>,
+    raw: Option<&[u8]>,
+    max_fee_per_gas: Option<i64>,
+    max_priority_fee_per_gas: Option<i64>,
+) -> AppResult<Option<SignedTransaction>> {
+    let (Some(tx_hash), Some(nonce), Some(raw)) = (tx_hash, nonce, raw) else {
+        return Ok(None);
+    };
+
+    Ok(Some(SignedTransaction {
+        tx_hash: decode_hash(tx_hash)?,
+        nonce: nonce as u64,
+        max_fee_per_gas: max_fee_per_gas.unwrap_or_default() as u128,
+        max_priority_fee_per_gas: max_priority_fee_per_gas.unwrap_or_default() as u128,
+        raw: raw.to_vec(),
+    }))
+}
+
+/// Decodes a `0x`-prefixed 32 byte hash as stored in `processed_jobs`
+pub fn decode_hash(value: &str) -> AppResult<[u8; 32]> {
+    let bytes = hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| AppError::Validation(format!("Invalid hash {}: {}", value, e)))?;
+    bytes
+        .try_into()
+        .map_err(|_| AppError::Validation(format!("Hash {} is not 32 bytes", value)))
+}
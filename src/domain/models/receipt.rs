@@ -0,0 +1,10 @@
+/// Outcome of a mined transaction as reported by the node
+#[derive(Debug, Clone)]
+pub struct TransactionReceipt {
+    pub tx_hash: [u8; 32],
+    pub block_number: u64,
+    pub block_hash: [u8; 32],
+    pub gas_used: u64,
+    pub effective_gas_price: u128,
+    pub success: bool,
+}
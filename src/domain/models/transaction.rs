@@ -1,19 +1,123 @@
+use alloy::primitives::{Address, Bytes, U256};
 use chrono::{DateTime, Utc};
 use serde::{Deserialize, Serialize};
+use std::fmt;
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct Transaction {
     pub id: i32,
     pub created_at: DateTime<Utc>,
     pub payload: serde_json::Value,
-    pub status: String,
+    pub status: TransactionStatus,
+    pub priority: TransactionPriority,
+    /// Not claimed before this time, e.g. until a settlement window has passed
+    pub execute_after: Option<DateTime<Utc>>,
+}
+
+/// Where a `transactions` row is in its hand-off to the worker
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "transaction_status", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionStatus {
+    /// Waiting to be claimed by a worker
+    Pending,
+    /// Handed off to a job in `processed_jobs`
+    Processed,
+}
+
+impl TransactionStatus {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionStatus::Pending => "pending",
+            TransactionStatus::Processed => "processed",
+        }
+    }
+}
+
+impl fmt::Display for TransactionStatus {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Urgency lane of a `transactions` row, ordered from least to most urgent
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Serialize, Deserialize, sqlx::Type)]
+#[sqlx(type_name = "transaction_priority", rename_all = "snake_case")]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionPriority {
+    Routine,
+    High,
+    /// E.g. recall notices that must not wait behind routine batches
+    Urgent,
+}
+
+impl TransactionPriority {
+    /// Every lane, most urgent first
+    pub const DESCENDING: [TransactionPriority; 3] =
+        [TransactionPriority::Urgent, TransactionPriority::High, TransactionPriority::Routine];
+
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            TransactionPriority::Routine => "routine",
+            TransactionPriority::High => "high",
+            TransactionPriority::Urgent => "urgent",
+        }
+    }
+}
+
+impl fmt::Display for TransactionPriority {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// What a transaction tips on top of the base fee
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Deserialize)]
+pub enum PriorityFee {
+    /// A fixed tip in wei
+    Fixed(u128),
+    /// The node's estimated tip scaled by this percentage
+    PercentOfEstimate(u64),
+}
+
+impl PriorityFee {
+    /// The node's estimate as it is
+    pub const ESTIMATE: PriorityFee = PriorityFee::PercentOfEstimate(100);
+
+    pub fn tip(&self, estimated_tip: u128) -> u128 {
+        match *self {
+            PriorityFee::Fixed(tip) => tip,
+            PriorityFee::PercentOfEstimate(percent) => estimated_tip.saturating_mul(u128::from(percent)) / 100,
+        }
+    }
 }
 
 #[derive(Debug, Serialize, Deserialize)]
 pub struct TransactionPayload {
+    /// Wei for native transfers, whole token units (e.g. "12.50") for token transfers
     pub amount: String,
     pub from: String,
     pub to: String,
+    /// ERC-20 contract address; native coin transfer when absent
+    #[serde(default)]
+    pub token: Option<String>,
+    /// Function signature such as `anchor(bytes32,string)` to call on `to`
+    #[serde(default)]
+    pub function: Option<String>,
+    /// JSON arguments for `function`, one per parameter
+    #[serde(default)]
+    pub args: Vec<serde_json::Value>,
+}
+
+/// What a validated payload asks the processor to send
+#[derive(Debug, Clone)]
+pub enum TransactionCall {
+    /// Native coin transfer of `value` wei
+    Native { to: Address, value: U256 },
+    /// ERC-20 transfer; `amount` is in whole token units until decimals are known
+    Token { token: Address, to: Address, amount: String },
+    /// Arbitrary contract call with ABI-encoded calldata
+    Contract { to: Address, value: U256, data: Bytes },
 }
 
 impl Transaction {
@@ -22,7 +126,9 @@ impl Transaction {
             id,
             created_at: Utc::now(),
             payload,
-            status: "pending".to_string(),
+            status: TransactionStatus::Pending,
+            priority: TransactionPriority::Routine,
+            execute_after: None,
         }
     }
 }
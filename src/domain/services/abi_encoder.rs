@@ -0,0 +1,107 @@
+use crate::error::{AppError, AppResult};
+use alloy::{
+    dyn_abi::{DynSolType, DynSolValue, JsonAbiExt, Specifier},
+    json_abi::Function,
+    primitives::Bytes,
+};
+
+/// Encodes calldata for a human-readable signature such as `anchor(bytes32,string)`
+/// with one JSON argument per parameter.
+pub fn encode_call(signature: &str, args: &[serde_json::Value]) -> AppResult<Bytes> {
+    let function = Function::parse(signature)
+        .map_err(|e| AppError::Validation(format!("Invalid function signature '{}': {}", signature, e)))?;
+
+    if function.inputs.len() != args.len() {
+        return Err(AppError::Validation(format!(
+            "{} expects {} arguments, got {}",
+            function.signature(),
+            function.inputs.len(),
+            args.len()
+        )));
+    }
+
+    let values = function
+        .inputs
+        .iter()
+        .zip(args)
+        .enumerate()
+        .map(|(index, (param, arg))| {
+            let ty: DynSolType = param
+                .resolve()
+                .map_err(|e| AppError::Validation(format!("Unsupported parameter type {}: {}", param.ty, e)))?;
+            coerce_arg(&ty, arg)
+                .map_err(|e| AppError::Validation(format!("Argument {} ({}): {}", index, param.ty, e)))
+        })
+        .collect::<AppResult<Vec<DynSolValue>>>()?;
+
+    let data = function
+        .abi_encode_input(&values)
+        .map_err(|e| AppError::Validation(format!("Failed to encode {}: {}", function.signature(), e)))?;
+
+    Ok(data.into())
+}
+
+/// Coerces a JSON argument into a Solidity value. Strings are parsed as-is,
+/// numbers, booleans and arrays through their JSON text.
+fn coerce_arg(ty: &DynSolType, arg: &serde_json::Value) -> Result<DynSolValue, String> {
+    let text = match arg {
+        serde_json::Value::String(s) => s.clone(),
+        other => other.to_string(),
+    };
+    ty.coerce_str(&text).map_err(|e| e.to_string())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn encodes_selector_and_static_arguments() {
+        let data = encode_call(
+            "transfer(address,uint256)",
+            &[
+                serde_json::json!("0x00000000000000000000000000000000000000aa"),
+                serde_json::json!(1000),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(data.len(), 4 + 2 * 32);
+        assert_eq!(&data[..4], &[0xa9, 0x05, 0x9c, 0xbb]);
+        assert_eq!(data[4 + 31], 0xaa);
+        assert_eq!(&data[4 + 32 + 30..], &[0x03, 0xe8]);
+    }
+
+    #[test]
+    fn accepts_numbers_as_strings_and_json() {
+        let from_string = encode_call("set(uint256)", &[serde_json::json!("42")]).unwrap();
+        let from_number = encode_call("set(uint256)", &[serde_json::json!(42)]).unwrap();
+
+        assert_eq!(from_string, from_number);
+    }
+
+    #[test]
+    fn encodes_bytes32_and_bool() {
+        let root = format!("0x{}", "11".repeat(32));
+        let data = encode_call("anchor(bytes32,bool)", &[serde_json::json!(root), serde_json::json!(true)]).unwrap();
+
+        assert_eq!(&data[4..36], &[0x11; 32]);
+        assert_eq!(data[4 + 32 + 31], 1);
+    }
+
+    #[test]
+    fn rejects_wrong_argument_count() {
+        let error = encode_call("set(uint256)", &[]).unwrap_err();
+
+        assert!(matches!(error, AppError::Validation(message) if message.contains("expects 1 arguments, got 0")));
+    }
+
+    #[test]
+    fn rejects_invalid_signature_and_arguments() {
+        assert!(matches!(encode_call("not a signature", &[]), Err(AppError::Validation(_))));
+        assert!(matches!(
+            encode_call("set(uint256)", &[serde_json::json!("not a number")]),
+            Err(AppError::Validation(_))
+        ));
+    }
+}
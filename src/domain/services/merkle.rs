@@ -0,0 +1,207 @@
+use alloy::primitives::keccak256;
+
+/// Hashes a record payload into a Merkle leaf: keccak256 of its canonical JSON,
+/// with object keys sorted so the hash does not depend on key order.
+pub fn hash_payload(payload: &serde_json::Value) -> [u8; 32] {
+    let mut canonical = String::new();
+    write_canonical(payload, &mut canonical);
+    keccak256(canonical.as_bytes()).0
+}
+
+fn write_canonical(value: &serde_json::Value, out: &mut String) {
+    match value {
+        serde_json::Value::Object(map) => {
+            let mut entries: Vec<_> = map.iter().collect();
+            entries.sort_by_key(|(key, _)| *key);
+
+            out.push('{');
+            for (index, (key, value)) in entries.into_iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                out.push_str(&serde_json::Value::String(key.clone()).to_string());
+                out.push(':');
+                write_canonical(value, out);
+            }
+            out.push('}');
+        }
+        serde_json::Value::Array(items) => {
+            out.push('[');
+            for (index, item) in items.iter().enumerate() {
+                if index > 0 {
+                    out.push(',');
+                }
+                write_canonical(item, out);
+            }
+            out.push(']');
+        }
+        scalar => out.push_str(&scalar.to_string()),
+    }
+}
+
+/// Hashes two nodes in sorted order, so proofs do not need left/right flags
+fn hash_pair(a: &[u8; 32], b: &[u8; 32]) -> [u8; 32] {
+    let (first, second) = if a <= b { (a, b) } else { (b, a) };
+    let mut buf = [0u8; 64];
+    buf[..32].copy_from_slice(first);
+    buf[32..].copy_from_slice(second);
+    keccak256(buf).0
+}
+
+/// Binary Merkle tree over payload hashes. An unpaired node at the end of a
+/// level is carried up unchanged.
+#[derive(Debug, Clone)]
+pub struct MerkleTree {
+    levels: Vec<Vec<[u8; 32]>>,
+}
+
+impl MerkleTree {
+    /// Builds the tree; `leaves` must not be empty
+    pub fn new(leaves: Vec<[u8; 32]>) -> Self {
+        assert!(!leaves.is_empty(), "Merkle tree needs at least one leaf");
+
+        let mut levels = vec![leaves];
+        while levels.last().is_some_and(|level| level.len() > 1) {
+            let next = levels
+                .last()
+                .unwrap()
+                .chunks(2)
+                .map(|pair| match pair {
+                    [a, b] => hash_pair(a, b),
+                    [a] => *a,
+                    _ => unreachable!(),
+                })
+                .collect();
+            levels.push(next);
+        }
+
+        Self { levels }
+    }
+
+    pub fn root(&self) -> [u8; 32] {
+        self.levels.last().unwrap()[0]
+    }
+
+    /// Sibling hashes from the leaf at `index` up to the root
+    pub fn proof(&self, index: usize) -> Vec<[u8; 32]> {
+        let mut proof = Vec::new();
+        let mut index = index;
+
+        for level in &self.levels[..self.levels.len() - 1] {
+            let sibling = index ^ 1;
+            if let Some(hash) = level.get(sibling) {
+                proof.push(*hash);
+            }
+            index /= 2;
+        }
+
+        proof
+    }
+
+    /// Recomputes the root from a leaf and its proof
+    pub fn compute_root(leaf: [u8; 32], proof: &[[u8; 32]]) -> [u8; 32] {
+        proof.iter().fold(leaf, |node, sibling| hash_pair(&node, sibling))
+    }
+}
+
+/// Whether `calldata` carries `commitment` either as the whole calldata (root sent
+/// to self) or as one of the 32-byte ABI words following a function selector
+pub fn calldata_commits_to(calldata: &[u8], commitment: &[u8; 32]) -> bool {
+    if calldata == commitment {
+        return true;
+    }
+
+    calldata
+        .get(4..)
+        .is_some_and(|args| args.chunks_exact(32).any(|word| word == commitment))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn leaves(count: u8) -> Vec<[u8; 32]> {
+        (0..count).map(|i| keccak256([i]).0).collect()
+    }
+
+    #[test]
+    fn every_proof_recomputes_the_root_for_odd_leaf_counts() {
+        for count in [1, 3, 5, 7, 9] {
+            let leaves = leaves(count);
+            let tree = MerkleTree::new(leaves.clone());
+
+            for (index, leaf) in leaves.iter().enumerate() {
+                assert_eq!(
+                    MerkleTree::compute_root(*leaf, &tree.proof(index)),
+                    tree.root(),
+                    "leaf {} of {}",
+                    index,
+                    count
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn unpaired_last_leaf_is_carried_up() {
+        let leaves = leaves(3);
+        let tree = MerkleTree::new(leaves.clone());
+
+        assert_eq!(tree.proof(2), vec![hash_pair(&leaves[0], &leaves[1])]);
+        assert_eq!(tree.root(), hash_pair(&hash_pair(&leaves[0], &leaves[1]), &leaves[2]));
+    }
+
+    #[test]
+    fn single_leaf_is_its_own_root() {
+        let leaves = leaves(1);
+        let tree = MerkleTree::new(leaves.clone());
+
+        assert!(tree.proof(0).is_empty());
+        assert_eq!(tree.root(), leaves[0]);
+    }
+
+    #[test]
+    fn proof_of_another_leaf_does_not_verify() {
+        let leaves = leaves(5);
+        let tree = MerkleTree::new(leaves.clone());
+
+        assert_ne!(MerkleTree::compute_root(leaves[0], &tree.proof(4)), tree.root());
+    }
+
+    #[test]
+    fn payload_hash_ignores_key_order() {
+        let a = serde_json::json!({ "to": "0x456", "amount": "100", "meta": { "b": 1, "a": [1, 2] } });
+        let b = serde_json::json!({ "meta": { "a": [1, 2], "b": 1 }, "amount": "100", "to": "0x456" });
+
+        assert_eq!(hash_payload(&a), hash_payload(&b));
+    }
+
+    #[test]
+    fn calldata_commits_to_a_root_sent_as_the_whole_calldata() {
+        let root = [7u8; 32];
+
+        assert!(calldata_commits_to(&root, &root));
+        assert!(!calldata_commits_to(&[8u8; 32], &root));
+    }
+
+    #[test]
+    fn calldata_commits_to_a_root_in_any_abi_word() {
+        let root = [7u8; 32];
+        let mut calldata = vec![0xde, 0xad, 0xbe, 0xef];
+        calldata.extend_from_slice(&[0u8; 32]);
+        calldata.extend_from_slice(&root);
+
+        assert!(calldata_commits_to(&calldata, &root));
+    }
+
+    #[test]
+    fn calldata_commits_to_ignores_misaligned_or_missing_roots() {
+        let root = [7u8; 32];
+        let mut misaligned = vec![0xde, 0xad, 0xbe, 0xef, 0x00];
+        misaligned.extend_from_slice(&root);
+
+        assert!(!calldata_commits_to(&misaligned, &root));
+        assert!(!calldata_commits_to(&[], &root));
+        assert!(!calldata_commits_to(&[0xde, 0xad, 0xbe, 0xef], &root));
+    }
+}
@@ -1 +1,4 @@
-pub mod transaction_processor; 
\ No newline at end of file
+pub mod abi_encoder;
+pub mod merkle;
+pub mod retry_policy;
+pub mod transaction_processor;
\ No newline at end of file
@@ -0,0 +1,114 @@
+use crate::{config::RetryConfig, error::AppError};
+use chrono::{DateTime, Utc};
+use rand::Rng;
+use std::time::Duration;
+
+/// Exponential backoff with jitter for failed jobs
+#[derive(Debug, Clone)]
+pub struct RetryPolicy {
+    max_attempts: i32,
+    base_delay: Duration,
+    max_delay: Duration,
+    jitter_percent: u32,
+}
+
+impl RetryPolicy {
+    pub fn new(config: &RetryConfig) -> Self {
+        Self {
+            max_attempts: config.max_attempts,
+            base_delay: Duration::from_secs(config.base_delay_seconds),
+            max_delay: Duration::from_secs(config.max_delay_seconds),
+            jitter_percent: config.jitter_percent.min(100),
+        }
+    }
+
+    /// Delay before the attempt following the `attempts`-th failure:
+    /// `base * 2^(attempts - 1)`, capped at `max_delay`, then spread by ±jitter
+    pub fn backoff(&self, attempts: i32) -> Duration {
+        let exponent = attempts.saturating_sub(1).clamp(0, 31) as u32;
+        let delay = self
+            .base_delay
+            .checked_mul(1 << exponent)
+            .map_or(self.max_delay, |delay| delay.min(self.max_delay));
+
+        if self.jitter_percent == 0 {
+            return delay;
+        }
+
+        let jitter = self.jitter_percent as f64 / 100.0;
+        delay.mul_f64(rand::thread_rng().gen_range(1.0 - jitter..=1.0 + jitter))
+    }
+
+    /// When to run the job again after its `attempts`-th failure with `error`,
+    /// or `None` when the error is permanent or the attempts are used up
+    pub fn next_attempt_at(&self, attempts: i32, error: &AppError) -> Option<DateTime<Utc>> {
+        if !error.is_retryable() || attempts >= self.max_attempts {
+            return None;
+        }
+
+        let backoff = chrono::Duration::from_std(self.backoff(attempts)).ok()?;
+        Some(Utc::now() + backoff)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::config::RetryConfig;
+
+    fn policy(jitter_percent: u32) -> RetryPolicy {
+        RetryPolicy::new(&RetryConfig {
+            poll_interval_seconds: 5,
+            max_attempts: 5,
+            base_delay_seconds: 10,
+            max_delay_seconds: 300,
+            jitter_percent,
+            batch_size: 10,
+        })
+    }
+
+    #[test]
+    fn backoff_doubles_per_attempt() {
+        let policy = policy(0);
+
+        assert_eq!(policy.backoff(1), Duration::from_secs(10));
+        assert_eq!(policy.backoff(2), Duration::from_secs(20));
+        assert_eq!(policy.backoff(3), Duration::from_secs(40));
+    }
+
+    #[test]
+    fn backoff_is_capped_at_max_delay() {
+        let policy = policy(0);
+
+        assert_eq!(policy.backoff(6), Duration::from_secs(300));
+        assert_eq!(policy.backoff(i32::MAX), Duration::from_secs(300));
+    }
+
+    #[test]
+    fn backoff_treats_attempts_below_one_as_the_first() {
+        let policy = policy(0);
+
+        assert_eq!(policy.backoff(0), Duration::from_secs(10));
+        assert_eq!(policy.backoff(-3), Duration::from_secs(10));
+    }
+
+    #[test]
+    fn backoff_jitter_stays_within_bounds() {
+        let policy = policy(20);
+
+        for _ in 0..100 {
+            let delay = policy.backoff(2);
+            assert!(delay >= Duration::from_secs(16) && delay <= Duration::from_secs(24), "{:?}", delay);
+        }
+    }
+
+    #[test]
+    fn no_retry_after_max_attempts_or_permanent_errors() {
+        let policy = policy(0);
+        let transient = AppError::Blockchain("connection reset".to_string());
+
+        assert!(policy.next_attempt_at(4, &transient).is_some());
+        assert!(policy.next_attempt_at(5, &transient).is_none());
+        assert!(policy.next_attempt_at(1, &AppError::Validation("bad address".to_string())).is_none());
+    }
+}
@@ -1,11 +1,19 @@
-use crate::domain::models::transaction::{Transaction, TransactionPayload};
+use crate::domain::models::{
+    broadcast::SignedTransaction,
+    transaction::{PriorityFee, Transaction, TransactionCall, TransactionPayload, TransactionPriority, TransactionStatus},
+};
+use crate::config::PriorityConfig;
+use crate::domain::services::{abi_encoder::encode_call, retry_policy::RetryPolicy};
 use crate::shared::traits::{BlockchainService, ProcessedJobsTracker as ProcessedJobsTrackerTrait, TransactionProcessor, TransactionRepository};
-use alloy::primitives::{Address, U256};
+use alloy::primitives::{
+    utils::{parse_units, ParseUnits},
+    Address, U256,
+};
 use async_trait::async_trait;
 use sqlx::PgPool;
 use std::{str::FromStr, sync::Arc};
-use tracing::{error, info, warn};
-use crate::error::AppResult;
+use tracing::{debug, error, info, warn};
+use crate::error::{AppError, AppResult};
 
 /// Postgres-based transaction repository
 pub struct PostgresTransactionRepository {
@@ -20,56 +28,324 @@ impl PostgresTransactionRepository {
 
 #[async_trait]
 impl TransactionRepository for PostgresTransactionRepository {
-    async fn fetch_new_transactions(&self, since: chrono::DateTime<chrono::Utc>) -> AppResult<Vec<Transaction>> {
-        let rows = sqlx::query!(
+    /// Leases up to `limit` due pending rows above `after_id` that no other
+    /// replica holds, taking over rows whose lease has expired. Scheduled rows
+    /// keep the cursor behind them until they are due. Without a `priority`
+    /// the most urgent rows are taken first.
+    async fn claim_new_transactions(
+        &self,
+        owner: &str,
+        after_id: i64,
+        priority: Option<TransactionPriority>,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> AppResult<Vec<Transaction>> {
+        let mut rows = sqlx::query!(
             r#"
-            SELECT id, created_at, payload, status
-            FROM transactions
-            WHERE created_at > $1
-            ORDER BY created_at
+            UPDATE transactions
+            SET lease_owner = $1, lease_expires_at = NOW() + make_interval(secs => $4::BIGINT)
+            WHERE id IN (
+                SELECT id
+                FROM transactions
+                WHERE id > $2::BIGINT
+                  AND status = 'pending'
+                  AND ($5::transaction_priority IS NULL OR priority = $5)
+                  AND (execute_after IS NULL OR execute_after <= NOW())
+                  AND (lease_expires_at IS NULL OR lease_expires_at < NOW() OR lease_owner = $1)
+                ORDER BY priority DESC, id
+                LIMIT $3
+                FOR UPDATE SKIP LOCKED
+            )
+            RETURNING id, created_at, payload, status AS "status: TransactionStatus",
+                priority AS "priority: TransactionPriority", execute_after
             "#,
-            since
+            owner,
+            after_id,
+            limit,
+            lease_seconds,
+            priority as Option<TransactionPriority>
         )
         .fetch_all(&self.pool)
         .await?;
+        rows.sort_by_key(|row| row.id);
 
         let transactions = rows
             .into_iter()
             .map(|row| Transaction {
                 id: row.id,
-                created_at: row.created_at.unwrap_or_else(|| chrono::Utc::now()),
+                created_at: row.created_at.unwrap_or_else(chrono::Utc::now),
                 payload: row.payload,
-                status: row.status.unwrap_or_else(|| "pending".to_string()),
+                status: row.status,
+                priority: row.priority,
+                execute_after: row.execute_after,
             })
             .collect();
 
         Ok(transactions)
     }
+
+    async fn complete_transaction(&self, id: i32, owner: &str) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET status = 'processed', lease_owner = NULL, lease_expires_at = NULL
+            WHERE id = $1 AND lease_owner = $2
+            "#,
+            id,
+            owner
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn release_transaction(&self, id: i32, owner: &str) -> AppResult<()> {
+        sqlx::query!(
+            r#"
+            UPDATE transactions
+            SET lease_owner = NULL, lease_expires_at = NULL
+            WHERE id = $1 AND lease_owner = $2
+            "#,
+            id,
+            owner
+        )
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    async fn find_transaction(&self, id: i32) -> AppResult<Option<Transaction>> {
+        let row = sqlx::query!(
+            r#"
+            SELECT id, created_at, payload, status AS "status: TransactionStatus",
+                priority AS "priority: TransactionPriority", execute_after
+            FROM transactions
+            WHERE id = $1
+            "#,
+            id
+        )
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(row.map(|row| Transaction {
+            id: row.id,
+            created_at: row.created_at.unwrap_or_else(chrono::Utc::now),
+            payload: row.payload,
+            status: row.status,
+            priority: row.priority,
+            execute_after: row.execute_after,
+        }))
+    }
+
+    async fn fetch_scheduled_transactions(&self, limit: i64) -> AppResult<Vec<Transaction>> {
+        let rows = sqlx::query!(
+            r#"
+            SELECT id, created_at, payload, status AS "status: TransactionStatus",
+                priority AS "priority: TransactionPriority", execute_after
+            FROM transactions
+            WHERE status = 'pending' AND execute_after > NOW()
+            ORDER BY execute_after, id
+            LIMIT $1
+            "#,
+            limit
+        )
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| Transaction {
+                id: row.id,
+                created_at: row.created_at.unwrap_or_else(chrono::Utc::now),
+                payload: row.payload,
+                status: row.status,
+                priority: row.priority,
+                execute_after: row.execute_after,
+            })
+            .collect())
+    }
 }
 
 /// Transaction processor that handles the business logic
 pub struct TransactionProcessorService {
     processed_jobs_tracker: Arc<dyn ProcessedJobsTrackerTrait + Send + Sync>,
     blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+    retry_policy: RetryPolicy,
+    priority: PriorityConfig,
 }
 
 impl TransactionProcessorService {
     pub fn new(
         processed_jobs_tracker: Arc<dyn ProcessedJobsTrackerTrait + Send + Sync>,
         blockchain_service: Arc<dyn BlockchainService + Send + Sync>,
+        retry_policy: RetryPolicy,
+        priority: PriorityConfig,
     ) -> Self {
         Self {
             processed_jobs_tracker,
             blockchain_service,
+            retry_policy,
+            priority,
+        }
+    }
+}
+
+impl TransactionProcessorService {
+    /// Parses and validates a payload without touching the chain
+    fn prepare_call(transaction: &Transaction) -> AppResult<TransactionCall> {
+        let payload: TransactionPayload = serde_json::from_value(transaction.payload.clone())
+            .map_err(|e| AppError::Validation(format!("Invalid payload: {}", e)))?;
+        let to = parse_address("to", &payload.to)?;
+
+        match (&payload.token, &payload.function) {
+            (Some(_), Some(_)) => Err(AppError::Validation(
+                "Payload cannot specify both token and function".to_string(),
+            )),
+            (Some(token), None) => Ok(TransactionCall::Token {
+                token: parse_address("token", token)?,
+                to,
+                amount: payload.amount,
+            }),
+            (None, Some(function)) => Ok(TransactionCall::Contract {
+                to,
+                value: parse_wei(&payload.amount)?,
+                data: encode_call(function, &payload.args)?,
+            }),
+            (None, None) => Ok(TransactionCall::Native {
+                to,
+                value: parse_wei(&payload.amount)?,
+            }),
+        }
+    }
+
+    /// Drives a claimed job through `validated`, `signed` and `broadcast`.
+    /// Every step is a guarded transition, so a job that was cancelled or
+    /// taken over in the meantime is left alone, and an interrupted job stays
+    /// in the last state it reached. `attempts` counts earlier failed attempts.
+    async fn run_job(&self, transaction: &Transaction, attempts: i32) -> AppResult<()> {
+        let record_id = transaction.id as i64;
+        let tracker = &self.processed_jobs_tracker;
+
+        let call = match Self::prepare_call(transaction) {
+            Ok(call) => call,
+            Err(e) => {
+                error!("Rejected payload of transaction {}: {}", transaction.id, e);
+                self.record_failure(record_id, attempts, &e).await?;
+                return Err(e);
+            }
+        };
+        if !tracker.mark_validated(record_id).await? {
+            warn!("Record {} left the claimed state before validation. Skipping.", record_id);
+            return Ok(());
+        }
+
+        let priority_fee = self.priority.lane(transaction.priority).priority_fee;
+        let signed = match self.sign_call(call, priority_fee).await {
+            Ok(signed) => signed,
+            Err(e) => {
+                error!("Failed to sign transaction for record {}: {}", record_id, e);
+                self.record_failure(record_id, attempts, &e).await?;
+                return Err(e);
+            }
+        };
+        // Nothing will broadcast a transaction the job does not record, so its
+        // nonce is released for a filler instead of blocking every later one
+        match tracker.mark_signed(record_id, &signed).await {
+            Ok(true) => {}
+            Ok(false) => {
+                warn!("Record {} left the validated state before signing. Skipping.", record_id);
+                self.release_nonce(signed.nonce).await;
+                return Ok(());
+            }
+            Err(e) => {
+                self.release_nonce(signed.nonce).await;
+                return Err(e);
+            }
+        }
+
+        // The node may have accepted the transaction despite the error, so the
+        // job stays signed and the stale job worker rebroadcasts the same bytes
+        // rather than signing a second payment at another nonce
+        match self.blockchain_service.broadcast_transaction(&signed).await {
+            Ok(sent) => {
+                tracker.mark_broadcast(record_id, &sent).await?;
+                Ok(())
+            }
+            Err(e) => {
+                error!(
+                    "Failed to broadcast transaction {} for record {}, leaving it signed: {}",
+                    signed.tx_hash_hex(),
+                    record_id,
+                    e
+                );
+                Err(e)
+            }
         }
     }
+
+    /// Releases a signed but unrecorded nonce, logging rather than failing so
+    /// the original outcome is kept
+    async fn release_nonce(&self, nonce: u64) {
+        if let Err(e) = self.blockchain_service.release_nonce(nonce).await {
+            error!("Failed to release nonce {}: {}", nonce, e);
+        }
+    }
+
+    /// Marks the job failed and schedules the next attempt if the error allows
+    /// one, otherwise moves it to the dead letters
+    async fn record_failure(&self, record_id: i64, attempts: i32, error: &AppError) -> AppResult<()> {
+        match self.retry_policy.next_attempt_at(attempts + 1, error) {
+            Some(next_attempt_at) => {
+                self.processed_jobs_tracker
+                    .mark_failed(record_id, &error.to_string(), next_attempt_at)
+                    .await
+            }
+            None => {
+                self.processed_jobs_tracker
+                    .dead_letter(record_id, &error.to_string())
+                    .await?;
+                Ok(())
+            }
+        }
+    }
+
+    /// Signs a validated call at a freshly reserved nonce, paying the lane's `priority_fee`
+    async fn sign_call(&self, call: TransactionCall, priority_fee: PriorityFee) -> AppResult<SignedTransaction> {
+        match call {
+            TransactionCall::Native { to, value } => self.blockchain_service.sign_transaction(to, value, priority_fee).await,
+            TransactionCall::Token { token, to, amount } => {
+                let decimals = self.blockchain_service.token_decimals(token).await?;
+                let amount = match parse_units(&amount, decimals) {
+                    Ok(ParseUnits::U256(amount)) => amount,
+                    Ok(ParseUnits::I256(_)) => {
+                        return Err(AppError::Validation("Token amount must not be negative".to_string()))
+                    }
+                    Err(e) => return Err(AppError::Validation(format!("Invalid token amount: {}", e))),
+                };
+                self.blockchain_service.sign_token_transfer(token, to, amount, priority_fee).await
+            }
+            TransactionCall::Contract { to, value, data } => {
+                self.blockchain_service.sign_contract_call(to, value, data, priority_fee).await
+            }
+        }
+    }
+}
+
+fn parse_address(field: &str, value: &str) -> AppResult<Address> {
+    Address::from_str(value).map_err(|e| AppError::Validation(format!("Invalid {} address: {}", field, e)))
+}
+
+fn parse_wei(amount: &str) -> AppResult<U256> {
+    U256::from_str_radix(amount, 10).map_err(|e| AppError::Validation(format!("Invalid amount: {}", e)))
 }
 
 #[async_trait]
 impl TransactionProcessor for TransactionProcessorService {
     async fn process_transaction(&self, transaction: &Transaction) -> AppResult<()> {
-        // Check if already processed
-        if self.processed_jobs_tracker.is_processed(transaction.id as i64).await? {
+        // Creating the job is the claim; losing it means the job already exists
+        if !self.processed_jobs_tracker.claim(transaction.id as i64).await? {
             warn!("Transaction ID {} has already been processed. Skipping.", transaction.id);
             return Ok(());
         }
@@ -79,31 +355,102 @@ impl TransactionProcessor for TransactionProcessorService {
             transaction.id,
             transaction.created_at.to_rfc3339()
         );
+        debug!("Transaction ID {} is in the {} lane", transaction.id, transaction.priority);
 
-        // Mark as pending before processing
-        self.processed_jobs_tracker.mark_pending(transaction.id as i64).await?;
-
-        // Parse the payload to get transaction details
-        let payload: TransactionPayload = serde_json::from_value(transaction.payload.clone())?;
-        let to_address = Address::from_str(&payload.to)
-            .map_err(|e| crate::error::AppError::Validation(format!("Invalid address: {}", e)))?;
-        let value = U256::from_str_radix(&payload.amount, 10)
-            .map_err(|e| crate::error::AppError::Validation(format!("Invalid amount: {}", e)))?;
-
-        // Send the transaction
-        match self.blockchain_service.send_transaction(to_address, value).await {
-            Ok(tx_hash) => {
-                // Mark as sent with transaction hash
-                let tx_hash_hex = format!("0x{}", hex::encode(tx_hash));
-                self.processed_jobs_tracker.mark_sent(transaction.id as i64, &tx_hash_hex).await?;
-            }
-            Err(e) => {
-                error!("Failed to send transaction for record {}: {}", transaction.id, e);
-                self.processed_jobs_tracker.mark_failed(transaction.id as i64).await?;
-                return Err(e);
-            }
-        }
+        self.run_job(transaction, 0).await
+    }
 
-        Ok(())
+    async fn retry_transaction(&self, transaction: &Transaction) -> AppResult<()> {
+        let record_id = transaction.id as i64;
+        let Some(attempts) = self.processed_jobs_tracker.claim_retry(record_id).await? else {
+            debug!("Transaction ID {} is no longer due for a retry. Skipping.", transaction.id);
+            return Ok(());
+        };
+
+        info!("Retrying transaction: id={}, attempt={}", transaction.id, attempts + 1);
+        self.run_job(transaction, attempts).await
     }
-} 
\ No newline at end of file
+} 
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::connection::test_pool;
+
+    async fn repository(name: &str, rows: i64) -> PostgresTransactionRepository {
+        let pool = test_pool(name).await;
+        // Drops the sample rows the initial migration seeds
+        sqlx::query("TRUNCATE transactions RESTART IDENTITY")
+            .execute(&pool)
+            .await
+            .unwrap();
+        sqlx::query("INSERT INTO transactions (payload) SELECT '{}'::jsonb FROM generate_series(1, $1)")
+            .bind(rows)
+            .execute(&pool)
+            .await
+            .unwrap();
+        PostgresTransactionRepository::new(pool)
+    }
+
+    async fn claim(repository: &PostgresTransactionRepository, owner: &str, limit: i64) -> Vec<i32> {
+        repository
+            .claim_new_transactions(owner, 0, None, limit, 60)
+            .await
+            .unwrap()
+            .iter()
+            .map(|transaction| transaction.id)
+            .collect()
+    }
+
+    #[tokio::test]
+    async fn claims_skip_rows_another_claim_has_locked() {
+        let repository = repository("lease_skip_locked", 4).await;
+        let mut db_tx = repository.pool.begin().await.unwrap();
+        sqlx::query("SELECT id FROM transactions WHERE id <= 2 FOR UPDATE")
+            .execute(&mut *db_tx)
+            .await
+            .unwrap();
+
+        assert_eq!(claim(&repository, "b", 10).await, vec![3, 4]);
+
+        db_tx.rollback().await.unwrap();
+    }
+
+    #[tokio::test]
+    async fn concurrent_claims_never_share_a_row() {
+        let repository = repository("lease_concurrent", 50).await;
+
+        let (a, b) = tokio::join!(claim(&repository, "a", 30), claim(&repository, "b", 30));
+
+        assert!(a.iter().all(|id| !b.contains(id)));
+        assert_eq!(a.len() + b.len(), 50);
+    }
+
+    #[tokio::test]
+    async fn leased_rows_are_taken_over_only_once_the_lease_expires() {
+        let repository = repository("lease_expiry", 3).await;
+        assert_eq!(claim(&repository, "a", 2).await, vec![1, 2]);
+
+        assert_eq!(claim(&repository, "b", 10).await, vec![3]);
+        assert_eq!(claim(&repository, "a", 10).await, vec![1, 2]);
+
+        sqlx::query("UPDATE transactions SET lease_expires_at = NOW() - INTERVAL '1 second' WHERE id = 1")
+            .execute(&repository.pool)
+            .await
+            .unwrap();
+        assert_eq!(claim(&repository, "b", 10).await, vec![1, 3]);
+    }
+
+    #[tokio::test]
+    async fn only_the_lease_owner_completes_or_releases_a_row() {
+        let repository = repository("lease_owner", 2).await;
+        assert_eq!(claim(&repository, "a", 10).await, vec![1, 2]);
+
+        repository.complete_transaction(1, "b").await.unwrap();
+        repository.release_transaction(2, "b").await.unwrap();
+        assert!(claim(&repository, "b", 10).await.is_empty());
+
+        repository.complete_transaction(1, "a").await.unwrap();
+        repository.release_transaction(2, "a").await.unwrap();
+        assert_eq!(claim(&repository, "b", 10).await, vec![2]);
+    }
+}
@@ -23,6 +23,12 @@ pub enum AppError {
     #[error("Validation error: {0}")]
     Validation(String),
 
+    #[error("Not found: {0}")]
+    NotFound(String),
+
+    #[error("Conflict: {0}")]
+    Conflict(String),
+
     #[error("IO error: {0}")]
     Io(#[from] std::io::Error),
 
@@ -32,6 +38,24 @@ pub enum AppError {
 
 pub type AppResult<T> = Result<T, AppError>;
 
+impl AppError {
+    /// Whether the same job may succeed when tried again later. Database, Redis,
+    /// IO and RPC errors are transient; bad input and reverts are permanent.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            AppError::Database(_) | AppError::Redis(_) | AppError::Io(_) => true,
+            AppError::Blockchain(message) => !message.contains("execution reverted"),
+            AppError::Config(_)
+            | AppError::Serialization(_)
+            | AppError::TransactionProcessing(_)
+            | AppError::Validation(_)
+            | AppError::NotFound(_)
+            | AppError::Conflict(_)
+            | AppError::Internal(_) => false,
+        }
+    }
+}
+
 impl From<anyhow::Error> for AppError {
     fn from(err: anyhow::Error) -> Self {
         AppError::Internal(err.to_string())
@@ -1,41 +1,559 @@
 use crate::{
-    error::AppResult,
+    config::BlockchainConfig,
+    domain::models::{
+        broadcast::{PublishedTransaction, SentTransaction, SignedTransaction},
+        leadership::FencingToken,
+        receipt::TransactionReceipt,
+        transaction::PriorityFee,
+    },
+    error::{AppError, AppResult},
+    infrastructure::blockchain::{erc20::IERC20, nonce_manager::NonceManager},
     shared::traits::BlockchainService,
 };
-use alloy::primitives::{Address, U256};
+use alloy::{
+    consensus::Transaction as _,
+    eips::{eip1559::Eip1559Estimation, eip2718::Encodable2718, BlockNumberOrTag},
+    network::{EthereumWallet, TransactionBuilder},
+    primitives::{Address, Bytes, B256, U256},
+    providers::{DynProvider, Provider, ProviderBuilder},
+    rpc::types::{Transaction as RpcTransaction, TransactionRequest},
+    signers::{local::PrivateKeySigner, Signer},
+    sol_types::SolCall,
+};
 use async_trait::async_trait;
-use std::time::Duration;
-use tracing::info;
-
+use tracing::{debug, error, info, warn};
 
+/// Blockchain client that signs with a local private key and broadcasts
+/// EIP-1559 transactions through an alloy HTTP provider.
+/// Nonces are assigned by the `NonceManager` rather than by the provider.
 #[derive(Clone)]
-pub struct BlockchainClient;
+pub struct BlockchainClient {
+    provider: DynProvider,
+    wallet: EthereumWallet,
+    nonce_manager: NonceManager,
+    signer_address: Address,
+    chain_id: u64,
+}
 
 impl BlockchainClient {
-    /// Creates a new simulated blockchain client.
-    /// The parameters are kept to maintain a consistent signature with the real client.
-    pub fn new(_rpc_url: &str, _private_key: &str) -> AppResult<Self> {
-        info!("Initializing SIMULATED Blockchain Client (v1.0 compatible)");
-        Ok(Self)
+    /// Creates a new client and checks that the RPC endpoint serves the configured chain.
+    pub async fn connect(config: &BlockchainConfig, nonce_manager: NonceManager) -> AppResult<Self> {
+        let signer: PrivateKeySigner = config
+            .private_key
+            .parse()
+            .map_err(|e| AppError::Config(format!("Invalid private key: {}", e)))?;
+        let signer = signer.with_chain_id(Some(config.chain_id));
+        let signer_address = signer.address();
+
+        let rpc_url = config
+            .rpc_url
+            .parse()
+            .map_err(|e| AppError::Config(format!("Invalid RPC URL: {}", e)))?;
+
+        let wallet = EthereumWallet::from(signer);
+        let provider = ProviderBuilder::new()
+            .wallet(wallet.clone())
+            .connect_http(rpc_url)
+            .erased();
+
+        let remote_chain_id = provider
+            .get_chain_id()
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to fetch chain id: {}", e)))?;
+        if remote_chain_id != config.chain_id {
+            return Err(AppError::Config(format!(
+                "CHAIN_ID is {} but the RPC endpoint reports chain {}",
+                config.chain_id, remote_chain_id
+            )));
+        }
+
+        let client = Self {
+            provider,
+            wallet,
+            nonce_manager,
+            signer_address,
+            chain_id: config.chain_id,
+        };
+
+        // Make sure transactions can be sent before the nonce worker's first resync
+        let pending_nonce = client.transaction_count(BlockNumberOrTag::Pending).await?;
+        client.nonce_manager.initialize(signer_address, pending_nonce).await?;
+
+        info!(
+            "Initialized Blockchain Client: chain_id={}, signer={}",
+            config.chain_id, signer_address
+        );
+
+        Ok(client)
+    }
+
+    async fn estimate_fees(&self) -> AppResult<Eip1559Estimation> {
+        self.provider
+            .estimate_eip1559_fees()
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to estimate fees: {}", e)))
+    }
+
+    /// Broadcasts `tx` with an explicit nonce and current EIP-1559 fees
+    async fn send_with_nonce(&self, tx: TransactionRequest, nonce: u64) -> AppResult<SentTransaction> {
+        let fees = self.estimate_fees().await?;
+        self.send_with_fees(tx, nonce, fees).await
+    }
+
+    /// Broadcasts `tx` with an explicit nonce and explicit EIP-1559 fees
+    async fn send_with_fees(
+        &self,
+        tx: TransactionRequest,
+        nonce: u64,
+        fees: Eip1559Estimation,
+    ) -> AppResult<SentTransaction> {
+        let tx = tx
+            .with_from(self.signer_address)
+            .with_nonce(nonce)
+            .with_chain_id(self.chain_id)
+            .with_max_fee_per_gas(fees.max_fee_per_gas)
+            .with_max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+
+        let pending = self
+            .provider
+            .send_transaction(tx)
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to broadcast transaction: {}", e)))?;
+
+        Ok(SentTransaction {
+            tx_hash: pending.tx_hash().0,
+            nonce,
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+        })
+    }
+
+    /// Reserves a nonce and signs `tx` with it and current EIP-1559 fees, with
+    /// the tip set by `priority_fee`.
+    /// A failed signing, or failing to record it, releases the nonce so the
+    /// gap can be filled later.
+    async fn sign_reserved(
+        &self,
+        tx: TransactionRequest,
+        priority_fee: PriorityFee,
+    ) -> AppResult<SignedTransaction> {
+        let nonce = self.nonce_manager.reserve(self.signer_address).await?;
+
+        let signed = match self.estimate_fees().await {
+            Ok(fees) => {
+                let fees = with_priority_fee(fees, priority_fee);
+                self.sign_with_fees(tx, nonce, fees).await
+            }
+            Err(e) => Err(e),
+        };
+        let signed = match signed {
+            Ok(signed) => signed,
+            Err(e) => {
+                self.try_release_nonce(nonce).await;
+                return Err(e);
+            }
+        };
+
+        if let Err(e) = self
+            .nonce_manager
+            .mark_signed(self.signer_address, nonce, &signed.tx_hash_hex())
+            .await
+        {
+            self.try_release_nonce(nonce).await;
+            return Err(e);
+        }
+        Ok(signed)
+    }
+
+    /// Signs `tx` locally without broadcasting it, estimating its gas unless it has a limit
+    async fn sign_with_fees(
+        &self,
+        tx: TransactionRequest,
+        nonce: u64,
+        fees: Eip1559Estimation,
+    ) -> AppResult<SignedTransaction> {
+        let tx = tx
+            .with_from(self.signer_address)
+            .with_nonce(nonce)
+            .with_chain_id(self.chain_id)
+            .with_max_fee_per_gas(fees.max_fee_per_gas)
+            .with_max_priority_fee_per_gas(fees.max_priority_fee_per_gas);
+
+        let gas_limit = match tx.gas {
+            Some(gas_limit) => gas_limit,
+            None => self
+                .provider
+                .estimate_gas(tx.clone())
+                .await
+                .map_err(|e| AppError::Blockchain(format!("Failed to estimate gas: {}", e)))?,
+        };
+
+        let envelope = tx
+            .with_gas_limit(gas_limit)
+            .build(&self.wallet)
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to sign transaction: {}", e)))?;
+
+        Ok(SignedTransaction {
+            tx_hash: envelope.tx_hash().0,
+            nonce,
+            max_fee_per_gas: fees.max_fee_per_gas,
+            max_priority_fee_per_gas: fees.max_priority_fee_per_gas,
+            raw: envelope.encoded_2718(),
+        })
+    }
+
+    /// Releases `nonce`, logging rather than failing so the original error is kept
+    async fn try_release_nonce(&self, nonce: u64) {
+        if let Err(e) = self.nonce_manager.release(self.signer_address, nonce).await {
+            error!("Failed to release nonce {}: {}", nonce, e);
+        }
+    }
+
+    /// Signs the transaction built by `build` at the nonce of the pending
+    /// transaction `tx_hash`, with bumped fees so the node accepts it as a replacement.
+    async fn sign_replacement_of(
+        &self,
+        tx_hash: [u8; 32],
+        bump_percent: u64,
+        max_fee_per_gas_cap: u128,
+        build: impl FnOnce(&RpcTransaction) -> TransactionRequest + Send,
+    ) -> AppResult<Option<SignedTransaction>> {
+        let original = self
+            .provider
+            .get_transaction_by_hash(B256::from(tx_hash))
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to fetch transaction: {}", e)))?;
+
+        let Some(original) = original else {
+            warn!("Transaction 0x{} is unknown to the node; cannot replace it", hex::encode(tx_hash));
+            return Ok(None);
+        };
+        if original.block_number.is_some() {
+            return Ok(None);
+        }
+        if original.max_fee_per_gas() >= max_fee_per_gas_cap {
+            warn!(
+                "Transaction 0x{} already pays the max fee cap of {} wei",
+                hex::encode(tx_hash),
+                max_fee_per_gas_cap
+            );
+            return Ok(None);
+        }
+
+        let bump = |fee: u128| fee + fee * u128::from(bump_percent) / 100;
+        let estimate = self.estimate_fees().await?;
+        let max_fee_per_gas = bump(original.max_fee_per_gas())
+            .max(estimate.max_fee_per_gas)
+            .min(max_fee_per_gas_cap);
+        let max_priority_fee_per_gas = bump(original.max_priority_fee_per_gas().unwrap_or_default())
+            .max(estimate.max_priority_fee_per_gas)
+            .min(max_fee_per_gas);
+
+        let signed = self
+            .sign_with_fees(
+                build(&original),
+                original.nonce(),
+                Eip1559Estimation {
+                    max_fee_per_gas,
+                    max_priority_fee_per_gas,
+                },
+            )
+            .await?;
+
+        info!(
+            "Signed replacement {} for transaction 0x{} at nonce {} (max fee {} wei)",
+            signed.tx_hash_hex(),
+            hex::encode(tx_hash),
+            signed.nonce,
+            signed.max_fee_per_gas
+        );
+
+        Ok(Some(signed))
+    }
+
+    async fn transaction_count(&self, block: BlockNumberOrTag) -> AppResult<u64> {
+        self.provider
+            .get_transaction_count(self.signer_address)
+            .block_id(block.into())
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to fetch transaction count: {}", e)))
+    }
+}
+
+/// Swaps the estimated tip for the one `priority_fee` asks for, moving the fee
+/// cap by the same amount so the headroom left for the base fee is unchanged
+fn with_priority_fee(fees: Eip1559Estimation, priority_fee: PriorityFee) -> Eip1559Estimation {
+    let tip = priority_fee.tip(fees.max_priority_fee_per_gas);
+    let base_fee_headroom = fees.max_fee_per_gas.saturating_sub(fees.max_priority_fee_per_gas);
+
+    Eip1559Estimation {
+        max_fee_per_gas: base_fee_headroom.saturating_add(tip),
+        max_priority_fee_per_gas: tip,
     }
 }
 
 #[async_trait]
 impl BlockchainService for BlockchainClient {
-    /// Simulates sending a transaction and returns a fake transaction hash.
-    async fn send_transaction(&self, to: Address, value: U256) -> AppResult<[u8; 32]> {
-        info!("SIMULATING sending transaction: to={}, value={}", to, value);
+    /// Address of the local signer that pays for every transaction.
+    fn signer_address(&self) -> Address {
+        self.signer_address
+    }
 
-        // Simulate network delay
-        tokio::time::sleep(Duration::from_millis(750)).await;
+    /// Signs an EIP-1559 native transfer at a freshly reserved nonce.
+    async fn sign_transaction(
+        &self,
+        to: Address,
+        value: U256,
+        priority_fee: PriorityFee,
+    ) -> AppResult<SignedTransaction> {
+        let tx = TransactionRequest::default().with_to(to).with_value(value);
+        let signed = self.sign_reserved(tx, priority_fee).await?;
+        info!(
+            "Signed transaction: to={}, value={}, nonce={}, tx_hash={}",
+            to, value, signed.nonce, signed.tx_hash_hex()
+        );
+
+        Ok(signed)
+    }
+
+    /// Signs an ERC-20 `transfer(to, amount)` call on `token`.
+    /// `amount` is in the token's base units.
+    async fn sign_token_transfer(
+        &self,
+        token: Address,
+        to: Address,
+        amount: U256,
+        priority_fee: PriorityFee,
+    ) -> AppResult<SignedTransaction> {
+        let data = IERC20::transferCall { to, amount }.abi_encode();
+        let tx = TransactionRequest::default().with_to(token).with_input(data);
+        let signed = self.sign_reserved(tx, priority_fee).await?;
+        info!(
+            "Signed token transfer: token={}, to={}, amount={}, nonce={}, tx_hash={}",
+            token, to, amount, signed.nonce, signed.tx_hash_hex()
+        );
+
+        Ok(signed)
+    }
 
-        // Create a fake, zeroed-out transaction hash
-        let fake_tx_hash = [0u8; 32];
+    /// Signs a call to `to` with pre-encoded calldata.
+    async fn sign_contract_call(
+        &self,
+        to: Address,
+        value: U256,
+        data: Bytes,
+        priority_fee: PriorityFee,
+    ) -> AppResult<SignedTransaction> {
+        let selector = data.get(..4).map(hex::encode).unwrap_or_default();
+        let tx = TransactionRequest::default().with_to(to).with_value(value).with_input(data);
+        let signed = self.sign_reserved(tx, priority_fee).await?;
         info!(
-            "SIMULATION successful. Fake tx_hash: 0x{}",
-            hex::encode(fake_tx_hash)
+            "Signed contract call: to={}, selector=0x{}, nonce={}, tx_hash={}",
+            to, selector, signed.nonce, signed.tx_hash_hex()
         );
 
-        Ok(fake_tx_hash)
+        Ok(signed)
+    }
+
+    /// Hands a signed transaction to the node and returns as soon as it is
+    /// accepted into the mempool. The bytes are sent as they are, so
+    /// broadcasting the same transaction again is harmless. A failed broadcast
+    /// keeps the nonce reserved, since the node may have accepted it anyway.
+    /// A transaction the node already has, pending or mined, counts as accepted.
+    async fn broadcast_transaction(&self, signed: &SignedTransaction) -> AppResult<SentTransaction> {
+        if let Err(e) = self.provider.send_raw_transaction(&signed.raw).await {
+            let message = e.to_string();
+            if message.contains("nonce too low") {
+                // The nonce is used up, possibly by this very transaction once it was mined
+                let known = self
+                    .provider
+                    .get_transaction_by_hash(B256::from(signed.tx_hash))
+                    .await
+                    .map_err(|e| AppError::Blockchain(format!("Failed to fetch transaction: {}", e)))?;
+                if known.is_none() {
+                    return Err(AppError::Blockchain(format!("Failed to broadcast transaction: {}", message)));
+                }
+            } else if !message.contains("already known") && !message.contains("known transaction") {
+                return Err(AppError::Blockchain(format!("Failed to broadcast transaction: {}", message)));
+            }
+            debug!("Node already knows transaction {}", signed.tx_hash_hex());
+        }
+
+        self.nonce_manager
+            .mark_broadcast(self.signer_address, signed.nonce, &signed.tx_hash_hex())
+            .await?;
+        info!("Broadcast transaction {} at nonce {}", signed.tx_hash_hex(), signed.nonce);
+
+        Ok(signed.sent())
+    }
+
+    async fn token_decimals(&self, token: Address) -> AppResult<u8> {
+        IERC20::new(token, &self.provider)
+            .decimals()
+            .call()
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to read decimals of token {}: {}", token, e)))
+    }
+
+    /// Signs a copy of a pending transaction at the same nonce with fees raised
+    /// by `bump_percent`, or to the current network estimate if that is higher.
+    /// Returns `None` when the original is no longer pending or already at the cap.
+    async fn sign_replacement(
+        &self,
+        tx_hash: [u8; 32],
+        bump_percent: u64,
+        max_fee_per_gas_cap: u128,
+    ) -> AppResult<Option<SignedTransaction>> {
+        self.sign_replacement_of(tx_hash, bump_percent, max_fee_per_gas_cap, |original| {
+            TransactionRequest::default()
+                .with_kind(original.kind())
+                .with_value(original.value())
+                .with_input(original.input().clone())
+                .with_gas_limit(original.gas_limit())
+        })
+        .await
+    }
+
+    /// Signs a zero-value self transfer at the nonce of a pending transaction,
+    /// which once mined keeps the original from ever being mined.
+    async fn sign_cancellation(
+        &self,
+        tx_hash: [u8; 32],
+        bump_percent: u64,
+        max_fee_per_gas_cap: u128,
+    ) -> AppResult<Option<SignedTransaction>> {
+        let signer_address = self.signer_address;
+        self.sign_replacement_of(tx_hash, bump_percent, max_fee_per_gas_cap, |_| {
+            TransactionRequest::default()
+                .with_to(signer_address)
+                .with_value(U256::ZERO)
+        })
+        .await
+    }
+
+    async fn get_transaction(&self, tx_hash: [u8; 32]) -> AppResult<Option<PublishedTransaction>> {
+        let transaction = self
+            .provider
+            .get_transaction_by_hash(B256::from(tx_hash))
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to fetch transaction: {}", e)))?;
+
+        let Some(transaction) = transaction else {
+            return Ok(None);
+        };
+
+        let block_timestamp = match transaction.block_number {
+            Some(block_number) => self
+                .provider
+                .get_block_by_number(BlockNumberOrTag::Number(block_number))
+                .await
+                .map_err(|e| AppError::Blockchain(format!("Failed to fetch block {}: {}", block_number, e)))?
+                .map(|block| block.header.timestamp),
+            None => None,
+        };
+
+        Ok(Some(PublishedTransaction {
+            tx_hash,
+            nonce: transaction.nonce(),
+            max_fee_per_gas: transaction.max_fee_per_gas(),
+            max_priority_fee_per_gas: transaction.max_priority_fee_per_gas().unwrap_or_default(),
+            input: transaction.input().to_vec(),
+            block_number: transaction.block_number,
+            block_timestamp,
+        }))
+    }
+
+    async fn get_transaction_receipt(&self, tx_hash: [u8; 32]) -> AppResult<Option<TransactionReceipt>> {
+        let receipt = self
+            .provider
+            .get_transaction_receipt(B256::from(tx_hash))
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to fetch receipt: {}", e)))?;
+
+        // A receipt without block data belongs to a pending transaction
+        let Some(receipt) = receipt else {
+            return Ok(None);
+        };
+        let (Some(block_number), Some(block_hash)) = (receipt.block_number, receipt.block_hash) else {
+            return Ok(None);
+        };
+
+        Ok(Some(TransactionReceipt {
+            tx_hash,
+            block_number,
+            block_hash: block_hash.0,
+            gas_used: receipt.gas_used,
+            effective_gas_price: receipt.effective_gas_price,
+            success: receipt.status(),
+        }))
+    }
+
+    async fn get_block_number(&self) -> AppResult<u64> {
+        self.provider
+            .get_block_number()
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to fetch block number: {}", e)))
+    }
+
+    async fn get_block_hash(&self, block_number: u64) -> AppResult<Option<[u8; 32]>> {
+        let block = self
+            .provider
+            .get_block_by_number(BlockNumberOrTag::Number(block_number))
+            .await
+            .map_err(|e| AppError::Blockchain(format!("Failed to fetch block {}: {}", block_number, e)))?;
+
+        Ok(block.map(|block| block.header.hash.0))
+    }
+
+    /// Number of transactions of the signer mined so far, i.e. the lowest nonce
+    /// that can still be used
+    async fn mined_nonce(&self) -> AppResult<u64> {
+        self.transaction_count(BlockNumberOrTag::Latest).await
+    }
+
+    /// Gives up on a reserved nonce so the nonce worker closes it with a filler
+    async fn release_nonce(&self, nonce: u64) -> AppResult<()> {
+        self.nonce_manager.release(self.signer_address, nonce).await
+    }
+
+    async fn resync_nonces(&self, fence: Option<&FencingToken>) -> AppResult<()> {
+        let pending_nonce = self.transaction_count(BlockNumberOrTag::Pending).await?;
+        self.nonce_manager.resync(self.signer_address, pending_nonce, fence).await
+    }
+
+    /// Closes every released nonce with a zero-value self transfer, unless the
+    /// chain has already moved past it. Stops as soon as `fence` is superseded,
+    /// since the new leader may have resynced the nonces in the meantime.
+    async fn fill_nonce_gaps(&self, fence: Option<&FencingToken>) -> AppResult<usize> {
+        let released = self.nonce_manager.released_nonces(self.signer_address).await?;
+        if released.is_empty() {
+            return Ok(0);
+        }
+
+        let mined_nonce = self.transaction_count(BlockNumberOrTag::Latest).await?;
+        let mut filled = 0;
+
+        for nonce in released {
+            if nonce < mined_nonce {
+                self.nonce_manager.mark_filled(self.signer_address, nonce, None, fence).await?;
+                continue;
+            }
+
+            self.nonce_manager.ensure_leadership(fence).await?;
+
+            let tx = TransactionRequest::default()
+                .with_to(self.signer_address)
+                .with_value(U256::ZERO);
+            let sent = self.send_with_nonce(tx, nonce).await?;
+            info!("Filled nonce gap {} with self transfer {}", nonce, sent.tx_hash_hex());
+
+            self.nonce_manager
+                .mark_filled(self.signer_address, nonce, Some(&sent.tx_hash_hex()), fence)
+                .await?;
+            filled += 1;
+        }
+
+        Ok(filled)
     }
 }
@@ -0,0 +1,10 @@
+use alloy::sol;
+
+sol! {
+    /// The subset of the ERC-20 interface used for stablecoin payouts
+    #[sol(rpc)]
+    interface IERC20 {
+        function transfer(address to, uint256 amount) external returns (bool);
+        function decimals() external view returns (uint8);
+    }
+}
@@ -1 +1,4 @@
-pub mod client; 
\ No newline at end of file
+pub mod client;
+pub mod erc20;
+pub mod nonce_manager;
+pub mod simulated;
\ No newline at end of file
@@ -0,0 +1,351 @@
+use crate::{
+    domain::models::leadership::FencingToken,
+    error::{AppError, AppResult},
+};
+use alloy::primitives::Address;
+use sqlx::{PgPool, Row};
+use std::time::Duration;
+use tracing::{debug, info, warn};
+
+/// Hands out nonces per signer address and remembers every reservation in Postgres,
+/// so nonces survive restarts and failed broadcasts leave a visible gap to fill.
+#[derive(Clone)]
+pub struct NonceManager {
+    pool: PgPool,
+    reservation_grace: Duration,
+}
+
+impl NonceManager {
+    pub fn new(pool: PgPool) -> Self {
+        Self {
+            pool,
+            reservation_grace: Duration::from_secs(60),
+        }
+    }
+
+    /// How long a reservation may stay unsigned before a resync treats it as
+    /// abandoned. Until then another replica may still be signing with it.
+    pub fn with_reservation_grace(mut self, reservation_grace: Duration) -> Self {
+        self.reservation_grace = reservation_grace;
+        self
+    }
+
+    /// Creates the counter for `address` if it does not exist yet, without touching an existing one
+    pub async fn initialize(&self, address: Address, pending_nonce: u64) -> AppResult<()> {
+        sqlx::query(
+            "INSERT INTO signer_nonces (address, next_nonce) VALUES ($1, $2) ON CONFLICT (address) DO NOTHING"
+        )
+        .bind(address.to_string())
+        .bind(to_db_nonce(pending_nonce)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Resets the counter to the node's pending transaction count.
+    /// Reservations at or above it never reached the chain and are dropped,
+    /// unfinished reservations below it were consumed by other transactions.
+    /// Signed reservations above it are kept for their rebroadcast, and so are
+    /// reservations made within the grace period, which may still be signing
+    /// on another replica. The nonces below them that nobody holds are released
+    /// to be filled. Nothing changes once `fence` has been superseded.
+    pub async fn resync(&self, address: Address, pending_nonce: u64, fence: Option<&FencingToken>) -> AppResult<()> {
+        let address = address.to_string();
+        let pending_nonce = to_db_nonce(pending_nonce)?;
+        let mut db_tx = self.pool.begin().await?;
+
+        if !Self::check_fence(&mut db_tx, fence).await? {
+            warn!("Skipped nonce resync for {}: leadership was lost", address);
+            return Ok(());
+        }
+
+        let previous: Option<i64> = sqlx::query_scalar(
+            "SELECT next_nonce FROM signer_nonces WHERE address = $1 FOR UPDATE"
+        )
+        .bind(&address)
+        .fetch_optional(&mut *db_tx)
+        .await?;
+
+        let in_flight_since = chrono::Utc::now()
+            - chrono::Duration::from_std(self.reservation_grace)
+                .map_err(|e| AppError::Config(format!("Invalid nonce reservation grace: {}", e)))?;
+
+        sqlx::query(
+            r#"
+            DELETE FROM nonce_reservations
+            WHERE address = $1
+              AND ((nonce >= $2 AND status <> 'signed' AND NOT (status = 'reserved' AND updated_at > $3))
+                OR (nonce < $2 AND status IN ('reserved', 'signed', 'released')))
+            "#
+        )
+        .bind(&address)
+        .bind(pending_nonce)
+        .bind(in_flight_since)
+        .execute(&mut *db_tx)
+        .await?;
+
+        let next_nonce: i64 = sqlx::query_scalar(
+            r#"
+            SELECT GREATEST($2, MAX(nonce) + 1) FROM nonce_reservations
+            WHERE address = $1 AND status IN ('signed', 'reserved')
+            "#
+        )
+        .bind(&address)
+        .bind(pending_nonce)
+        .fetch_one(&mut *db_tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO signer_nonces (address, next_nonce) VALUES ($1, $2)
+            ON CONFLICT (address) DO UPDATE SET next_nonce = EXCLUDED.next_nonce, updated_at = CURRENT_TIMESTAMP
+            "#
+        )
+        .bind(&address)
+        .bind(next_nonce)
+        .execute(&mut *db_tx)
+        .await?;
+
+        sqlx::query(
+            r#"
+            INSERT INTO nonce_reservations (address, nonce, status)
+            SELECT $1, gap, 'released' FROM generate_series($2::BIGINT, $3::BIGINT - 1) AS gap
+            ON CONFLICT (address, nonce) DO NOTHING
+            "#
+        )
+        .bind(&address)
+        .bind(pending_nonce)
+        .bind(next_nonce)
+        .execute(&mut *db_tx)
+        .await?;
+
+        db_tx.commit().await?;
+
+        match previous {
+            Some(previous) if previous != next_nonce => warn!(
+                "Resynced nonce for {} from {} to {}",
+                address, previous, next_nonce
+            ),
+            _ => info!("Nonce for {} synced at {}", address, next_nonce),
+        }
+
+        Ok(())
+    }
+
+    /// Atomically reserves the next nonce for `address`
+    pub async fn reserve(&self, address: Address) -> AppResult<u64> {
+        let address = address.to_string();
+        let mut db_tx = self.pool.begin().await?;
+
+        let nonce: Option<i64> = sqlx::query_scalar(
+            r#"
+            UPDATE signer_nonces SET next_nonce = next_nonce + 1, updated_at = CURRENT_TIMESTAMP
+            WHERE address = $1
+            RETURNING next_nonce - 1
+            "#
+        )
+        .bind(&address)
+        .fetch_optional(&mut *db_tx)
+        .await?;
+
+        let nonce = nonce.ok_or_else(|| {
+            AppError::Blockchain(format!("Nonce for {} has not been synced yet", address))
+        })?;
+
+        sqlx::query(
+            "INSERT INTO nonce_reservations (address, nonce, status) VALUES ($1, $2, 'reserved')"
+        )
+        .bind(&address)
+        .bind(nonce)
+        .execute(&mut *db_tx)
+        .await?;
+
+        db_tx.commit().await?;
+
+        debug!("Reserved nonce {} for {}", nonce, address);
+        Ok(nonce as u64)
+    }
+
+    /// Records that a transaction was signed with `nonce` and may be broadcast at any time
+    pub async fn mark_signed(&self, address: Address, nonce: u64, tx_hash: &str) -> AppResult<()> {
+        self.set_status(address, nonce, "signed", Some(tx_hash)).await
+    }
+
+    /// Records that the transaction using `nonce` was accepted by the node
+    pub async fn mark_broadcast(&self, address: Address, nonce: u64, tx_hash: &str) -> AppResult<()> {
+        self.set_status(address, nonce, "broadcast", Some(tx_hash)).await
+    }
+
+    /// Gives up on `nonce` after a failed broadcast, leaving a gap to be filled
+    pub async fn release(&self, address: Address, nonce: u64) -> AppResult<()> {
+        warn!("Released nonce {} for {}", nonce, address);
+        self.set_status(address, nonce, "released", None).await
+    }
+
+    /// Records that the gap at `nonce` is closed, by a filler transaction or by the chain
+    pub async fn mark_filled(
+        &self,
+        address: Address,
+        nonce: u64,
+        tx_hash: Option<&str>,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<()> {
+        let mut db_tx = self.pool.begin().await?;
+        if !Self::check_fence(&mut db_tx, fence).await? {
+            return Err(lost_leadership(fence));
+        }
+
+        sqlx::query(
+            r#"
+            UPDATE nonce_reservations
+            SET status = 'filled', tx_hash = COALESCE($1, tx_hash), updated_at = CURRENT_TIMESTAMP
+            WHERE address = $2 AND nonce = $3
+            "#
+        )
+        .bind(tx_hash)
+        .bind(address.to_string())
+        .bind(to_db_nonce(nonce)?)
+        .execute(&mut *db_tx)
+        .await?;
+
+        db_tx.commit().await?;
+        Ok(())
+    }
+
+    /// Fails once `fence` has been superseded, before anything is sent on its behalf
+    pub async fn ensure_leadership(&self, fence: Option<&FencingToken>) -> AppResult<()> {
+        let mut db_tx = self.pool.begin().await?;
+        match Self::check_fence(&mut db_tx, fence).await? {
+            true => Ok(()),
+            false => Err(lost_leadership(fence)),
+        }
+    }
+
+    /// Share-locks the role's token until `db_tx` ends, so no successor can
+    /// register while the caller writes
+    async fn check_fence(
+        db_tx: &mut sqlx::Transaction<'_, sqlx::Postgres>,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<bool> {
+        let holds: bool = sqlx::query_scalar("SELECT holds_leadership($1, $2)")
+            .bind(fence.map(|fence| fence.role.as_str()))
+            .bind(fence.map(|fence| fence.token))
+            .fetch_one(&mut **db_tx)
+            .await?;
+
+        Ok(holds)
+    }
+
+    /// Released nonces that still need a filler transaction, lowest first
+    pub async fn released_nonces(&self, address: Address) -> AppResult<Vec<u64>> {
+        let rows = sqlx::query(
+            "SELECT nonce FROM nonce_reservations WHERE address = $1 AND status = 'released' ORDER BY nonce"
+        )
+        .bind(address.to_string())
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| row.get::<i64, _>("nonce") as u64)
+            .collect())
+    }
+
+    async fn set_status(&self, address: Address, nonce: u64, status: &str, tx_hash: Option<&str>) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            UPDATE nonce_reservations
+            SET status = $1, tx_hash = COALESCE($2, tx_hash), updated_at = CURRENT_TIMESTAMP
+            WHERE address = $3 AND nonce = $4
+            "#
+        )
+        .bind(status)
+        .bind(tx_hash)
+        .bind(address.to_string())
+        .bind(to_db_nonce(nonce)?)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(())
+    }
+}
+
+fn lost_leadership(fence: Option<&FencingToken>) -> AppError {
+    let role = fence.map(|fence| fence.role.as_str()).unwrap_or_default();
+    AppError::Blockchain(format!("Leadership of {} was taken over", role))
+}
+
+fn to_db_nonce(nonce: u64) -> AppResult<i64> {
+    i64::try_from(nonce).map_err(|_| AppError::Blockchain(format!("Nonce {} does not fit into BIGINT", nonce)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::connection::test_pool;
+
+    const SIGNER: Address = Address::repeat_byte(0x11);
+
+    async fn reserve_many(nonce_manager: &NonceManager, count: usize) -> Vec<u64> {
+        let mut nonces = Vec::new();
+        for _ in 0..count {
+            nonces.push(nonce_manager.reserve(SIGNER).await.unwrap());
+        }
+        nonces
+    }
+
+    #[tokio::test]
+    async fn resync_releases_the_gap_below_a_signed_reservation() {
+        let nonce_manager = NonceManager::new(test_pool("nonce_gap_below_signed").await)
+            .with_reservation_grace(Duration::ZERO);
+        nonce_manager.initialize(SIGNER, 0).await.unwrap();
+        assert_eq!(reserve_many(&nonce_manager, 3).await, vec![0, 1, 2]);
+        nonce_manager.mark_signed(SIGNER, 2, "0x02").await.unwrap();
+
+        nonce_manager.resync(SIGNER, 0, None).await.unwrap();
+
+        assert_eq!(nonce_manager.released_nonces(SIGNER).await.unwrap(), vec![0, 1]);
+        assert_eq!(nonce_manager.reserve(SIGNER).await.unwrap(), 3);
+    }
+
+    #[tokio::test]
+    async fn resync_keeps_reservations_made_within_the_grace_period() {
+        let nonce_manager = NonceManager::new(test_pool("nonce_grace_period").await);
+        nonce_manager.initialize(SIGNER, 0).await.unwrap();
+        reserve_many(&nonce_manager, 2).await;
+
+        nonce_manager.resync(SIGNER, 0, None).await.unwrap();
+
+        assert!(nonce_manager.released_nonces(SIGNER).await.unwrap().is_empty());
+        assert_eq!(nonce_manager.reserve(SIGNER).await.unwrap(), 2);
+    }
+
+    #[tokio::test]
+    async fn resync_drops_reservations_the_chain_moved_past() {
+        let nonce_manager = NonceManager::new(test_pool("nonce_chain_moved_past").await)
+            .with_reservation_grace(Duration::ZERO);
+        nonce_manager.initialize(SIGNER, 0).await.unwrap();
+        reserve_many(&nonce_manager, 3).await;
+        nonce_manager.release(SIGNER, 1).await.unwrap();
+
+        nonce_manager.resync(SIGNER, 5, None).await.unwrap();
+
+        assert!(nonce_manager.released_nonces(SIGNER).await.unwrap().is_empty());
+        assert_eq!(nonce_manager.reserve(SIGNER).await.unwrap(), 5);
+    }
+
+    #[tokio::test]
+    async fn filled_nonces_are_no_longer_released() {
+        let nonce_manager = NonceManager::new(test_pool("nonce_filled").await)
+            .with_reservation_grace(Duration::ZERO);
+        nonce_manager.initialize(SIGNER, 0).await.unwrap();
+        reserve_many(&nonce_manager, 3).await;
+        nonce_manager.release(SIGNER, 0).await.unwrap();
+        nonce_manager.release(SIGNER, 1).await.unwrap();
+
+        nonce_manager.mark_filled(SIGNER, 0, Some("0x00"), None).await.unwrap();
+
+        assert_eq!(nonce_manager.released_nonces(SIGNER).await.unwrap(), vec![1]);
+    }
+}
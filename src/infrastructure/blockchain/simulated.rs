@@ -0,0 +1,163 @@
+use crate::{
+    domain::models::{
+        broadcast::{PublishedTransaction, SentTransaction, SignedTransaction},
+        leadership::FencingToken,
+        receipt::TransactionReceipt,
+        transaction::PriorityFee,
+    },
+    error::AppResult,
+    shared::traits::BlockchainService,
+};
+use alloy::primitives::{Address, Bytes, U256};
+use async_trait::async_trait;
+use std::time::{Duration, SystemTime, UNIX_EPOCH};
+use tracing::info;
+
+
+/// Blockchain client that never touches the network.
+/// Selected with `BLOCKCHAIN_MODE=simulated` for local development.
+#[derive(Clone, Default)]
+pub struct SimulatedBlockchainClient;
+
+/// Block time used to derive the simulated chain height from the wall clock
+const SIMULATED_BLOCK_TIME_SECS: u64 = 12;
+
+impl SimulatedBlockchainClient {
+    /// Creates a new simulated blockchain client.
+    pub fn new() -> Self {
+        info!("Initializing SIMULATED Blockchain Client (v1.0 compatible)");
+        Self
+    }
+
+    fn fake_signed() -> SignedTransaction {
+        SignedTransaction {
+            tx_hash: [0u8; 32],
+            nonce: 0,
+            max_fee_per_gas: 0,
+            max_priority_fee_per_gas: 0,
+            raw: Vec::new(),
+        }
+    }
+
+    fn current_block() -> u64 {
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap_or_default()
+            .as_secs();
+        now / SIMULATED_BLOCK_TIME_SECS
+    }
+}
+
+#[async_trait]
+impl BlockchainService for SimulatedBlockchainClient {
+    fn signer_address(&self) -> Address {
+        Address::ZERO
+    }
+
+    /// Simulates signing a native transfer with a fake, zeroed-out transaction hash.
+    async fn sign_transaction(&self, to: Address, value: U256, _priority_fee: PriorityFee) -> AppResult<SignedTransaction> {
+        info!("SIMULATING signing transaction: to={}, value={}", to, value);
+        Ok(Self::fake_signed())
+    }
+
+    /// Simulates signing an ERC-20 transfer.
+    async fn sign_token_transfer(
+        &self,
+        token: Address,
+        to: Address,
+        amount: U256,
+        _priority_fee: PriorityFee,
+    ) -> AppResult<SignedTransaction> {
+        info!("SIMULATING signing token transfer: token={}, to={}, amount={}", token, to, amount);
+        Ok(Self::fake_signed())
+    }
+
+    /// Simulates signing a contract call.
+    async fn sign_contract_call(
+        &self,
+        to: Address,
+        value: U256,
+        data: Bytes,
+        _priority_fee: PriorityFee,
+    ) -> AppResult<SignedTransaction> {
+        info!("SIMULATING signing contract call: to={}, value={}, data={}", to, value, data);
+        Ok(Self::fake_signed())
+    }
+
+    /// Simulates broadcasting after a network delay.
+    async fn broadcast_transaction(&self, signed: &SignedTransaction) -> AppResult<SentTransaction> {
+        tokio::time::sleep(Duration::from_millis(750)).await;
+        info!("SIMULATION successful. Fake tx_hash: {}", signed.tx_hash_hex());
+
+        Ok(signed.sent())
+    }
+
+    /// Every simulated token uses 18 decimals.
+    async fn token_decimals(&self, _token: Address) -> AppResult<u8> {
+        Ok(18)
+    }
+
+    /// Simulated transactions are mined instantly, so there is never anything to replace.
+    async fn sign_replacement(
+        &self,
+        _tx_hash: [u8; 32],
+        _bump_percent: u64,
+        _max_fee_per_gas_cap: u128,
+    ) -> AppResult<Option<SignedTransaction>> {
+        Ok(None)
+    }
+
+    /// Simulated transactions are mined instantly, so there is never anything to cancel.
+    async fn sign_cancellation(
+        &self,
+        _tx_hash: [u8; 32],
+        _bump_percent: u64,
+        _max_fee_per_gas_cap: u128,
+    ) -> AppResult<Option<SignedTransaction>> {
+        Ok(None)
+    }
+
+    /// Simulated transactions carry no calldata, so there is nothing to fetch.
+    async fn get_transaction(&self, _tx_hash: [u8; 32]) -> AppResult<Option<PublishedTransaction>> {
+        Ok(None)
+    }
+
+    /// Reports every transaction as successfully mined in the genesis block,
+    /// so simulated jobs become confirmed on the next confirmation pass.
+    async fn get_transaction_receipt(&self, tx_hash: [u8; 32]) -> AppResult<Option<TransactionReceipt>> {
+        Ok(Some(TransactionReceipt {
+            tx_hash,
+            block_number: 0,
+            block_hash: [0u8; 32],
+            gas_used: 21_000,
+            effective_gas_price: 0,
+            success: true,
+        }))
+    }
+
+    async fn get_block_number(&self) -> AppResult<u64> {
+        Ok(Self::current_block())
+    }
+
+    /// The simulated chain never reorganises, so every block hash is zeroed like the receipts.
+    async fn get_block_hash(&self, block_number: u64) -> AppResult<Option<[u8; 32]>> {
+        Ok((block_number <= Self::current_block()).then_some([0u8; 32]))
+    }
+
+    /// Simulated transactions do not use nonces.
+    async fn mined_nonce(&self) -> AppResult<u64> {
+        Ok(0)
+    }
+
+    async fn release_nonce(&self, _nonce: u64) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn resync_nonces(&self, _fence: Option<&FencingToken>) -> AppResult<()> {
+        Ok(())
+    }
+
+    async fn fill_nonce_gaps(&self, _fence: Option<&FencingToken>) -> AppResult<usize> {
+        Ok(0)
+    }
+}
@@ -1,9 +1,11 @@
 use crate::error::AppResult;
 use sqlx::postgres::PgPoolOptions;
 use sqlx::PgPool;
-use std::fs;
+use std::{collections::HashSet, fs};
 use tracing::info;
 
+const MIGRATIONS_DIR: &str = "migrations";
+
 pub async fn create_pool(database_url: &str) -> AppResult<PgPool> {
     let pool = PgPoolOptions::new()
         .max_connections(5)
@@ -13,23 +15,114 @@ pub async fn create_pool(database_url: &str) -> AppResult<PgPool> {
     Ok(pool)
 }
 
+/// Before migrations were tracked the service only ran `init.sql`, now
+/// `001_init`, on every start, so that is all an untracked database has.
+const UNTRACKED_BASELINE: &str = "001_init";
+
+/// Applies each `.sql` file in `migrations/` that has not run yet, in file name
+/// order, and records it in `schema_migrations`. A file runs in the same
+/// transaction as its record, so a failed migration is retried on the next start.
 pub async fn run_migrations(pool: &PgPool) -> AppResult<()> {
     info!("Running database migrations...");
-    
-    let sql_content = fs::read_to_string("migrations/init.sql")?;
-    
-    let statements: Vec<&str> = sql_content
-        .split(';')
-        .map(|s| s.trim())
-        .filter(|s| !s.is_empty() && !s.starts_with("--"))
+
+    let mut files: Vec<_> = fs::read_dir(MIGRATIONS_DIR)?
+        .filter_map(|entry| entry.ok().map(|entry| entry.path()))
+        .filter(|path| path.extension().is_some_and(|ext| ext == "sql"))
         .collect();
-    
-    for statement in statements {
-        if !statement.trim().is_empty() {
-            sqlx::query(statement).execute(pool).await?;
+    files.sort();
+
+    let mut applied = tracked_migrations(pool).await?;
+
+    for file in files {
+        let Some(version) = file.file_stem().and_then(|stem| stem.to_str()).map(str::to_string) else {
+            continue;
+        };
+        if applied.contains(&version) {
+            continue;
         }
+
+        let sql_content = fs::read_to_string(&file)?;
+        let mut db_tx = pool.begin().await?;
+        sqlx::raw_sql(&sql_content).execute(&mut *db_tx).await?;
+        sqlx::query("INSERT INTO schema_migrations (version) VALUES ($1)")
+            .bind(&version)
+            .execute(&mut *db_tx)
+            .await?;
+        db_tx.commit().await?;
+
+        info!("Applied migration {}", file.display());
+        applied.insert(version);
     }
-    
+
     info!("Database migrations completed successfully");
     Ok(())
 }
+
+/// Versions already applied. The first tracked start of a database created
+/// before tracking records only the initial schema, so every later migration runs.
+async fn tracked_migrations(pool: &PgPool) -> AppResult<HashSet<String>> {
+    let (tracked, existing): (bool, bool) = sqlx::query_as(
+        "SELECT to_regclass('schema_migrations') IS NOT NULL, to_regclass('processed_jobs') IS NOT NULL"
+    )
+    .fetch_one(pool)
+    .await?;
+
+    if !tracked {
+        sqlx::raw_sql(
+            r#"
+            CREATE TABLE schema_migrations (
+                version TEXT PRIMARY KEY,
+                applied_at TIMESTAMP WITH TIME ZONE DEFAULT CURRENT_TIMESTAMP
+            )
+            "#
+        )
+        .execute(pool)
+        .await?;
+    }
+
+    if !tracked && existing {
+        info!("Baselining untracked database at migration {}", UNTRACKED_BASELINE);
+
+        sqlx::query("INSERT INTO schema_migrations (version) VALUES ($1) ON CONFLICT DO NOTHING")
+            .bind(UNTRACKED_BASELINE)
+            .execute(pool)
+            .await?;
+    }
+
+    let versions: Vec<String> = sqlx::query_scalar("SELECT version FROM schema_migrations")
+        .fetch_all(pool)
+        .await?;
+
+    Ok(versions.into_iter().collect())
+}
+
+/// Recreates the database `test_{name}` on the server behind `DATABASE_URL`
+/// and migrates it, so each test that needs Postgres starts from a clean schema
+#[cfg(test)]
+pub async fn test_pool(name: &str) -> PgPool {
+    use sqlx::{postgres::PgConnectOptions, Connection, PgConnection};
+
+    let url = std::env::var("DATABASE_URL").expect("DATABASE_URL must point at Postgres to run database tests");
+    let options: PgConnectOptions = url.parse().expect("DATABASE_URL must be a valid Postgres URL");
+    let database = format!("test_{}", name);
+
+    let mut admin = PgConnection::connect_with(&options).await.unwrap();
+    sqlx::raw_sql(&format!("DROP DATABASE IF EXISTS {} WITH (FORCE)", database))
+        .execute(&mut admin)
+        .await
+        .unwrap();
+    sqlx::raw_sql(&format!("CREATE DATABASE {}", database))
+        .execute(&mut admin)
+        .await
+        .unwrap();
+    admin.close().await.unwrap();
+
+    let pool = PgPoolOptions::new()
+        .max_connections(5)
+        .connect_with(options.database(&database))
+        .await
+        .unwrap();
+    run_migrations(&pool).await.unwrap();
+
+    pool
+}
@@ -0,0 +1,48 @@
+use crate::{error::AppResult, shared::traits::TransactionListener};
+use async_trait::async_trait;
+use sqlx::{postgres::PgListener, PgPool};
+use std::time::Duration;
+use tracing::{debug, info};
+
+/// Channel the `transactions` insert trigger notifies on
+pub const NEW_TRANSACTIONS_CHANNEL: &str = "new_transactions";
+
+/// Listens for `pg_notify` calls made by the `transactions` insert trigger
+pub struct PostgresTransactionListener {
+    listener: PgListener,
+}
+
+impl PostgresTransactionListener {
+    pub async fn connect(pool: &PgPool) -> AppResult<Self> {
+        let mut listener = PgListener::connect_with(pool).await?;
+        listener.listen(NEW_TRANSACTIONS_CHANNEL).await?;
+        info!("Listening for notifications on '{}'", NEW_TRANSACTIONS_CHANNEL);
+
+        Ok(Self { listener })
+    }
+}
+
+#[async_trait]
+impl TransactionListener for PostgresTransactionListener {
+    /// Waits for a notification, then drops the ones that already arrived
+    /// behind it: a single claim picks up all of their rows, and waking up for
+    /// each would only run empty polls. `try_recv` takes what is buffered
+    /// first, and a zero timeout keeps it from waiting on the socket.
+    async fn wait_for_notification(&mut self) -> AppResult<()> {
+        let notification = self.listener.recv().await?;
+        debug!("New transaction notification: id={}", notification.payload());
+
+        let mut drained = 0;
+        while let Ok(next) = tokio::time::timeout(Duration::ZERO, self.listener.try_recv()).await {
+            if next?.is_none() {
+                break;
+            }
+            drained += 1;
+        }
+        if drained > 0 {
+            debug!("Drained {} more buffered notifications", drained);
+        }
+
+        Ok(())
+    }
+}
@@ -1,2 +1,3 @@
 pub mod connection;
-pub mod repositories; 
\ No newline at end of file
+pub mod listener;
+pub mod repositories;
\ No newline at end of file
@@ -0,0 +1,96 @@
+use crate::{
+    domain::models::{
+        anchor::{AnchorBatch, AnchorLeaf},
+        processed_job::decode_hash,
+    },
+    error::AppResult,
+    shared::traits::AnchorStore,
+};
+use async_trait::async_trait;
+use sqlx::{PgPool, Row};
+use tracing::info;
+
+pub struct AnchorRepository {
+    pool: PgPool,
+}
+
+impl AnchorRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl AnchorStore for AnchorRepository {
+    async fn save_batch(&self, batch: &AnchorBatch) -> AppResult<i64> {
+        let mut db_tx = self.pool.begin().await?;
+
+        let batch_id: i64 = sqlx::query_scalar(
+            "INSERT INTO anchor_batches (merkle_root, tx_hash, leaf_count) VALUES ($1, $2, $3) RETURNING id"
+        )
+        .bind(format!("0x{}", hex::encode(batch.merkle_root)))
+        .bind(&batch.tx_hash)
+        .bind(batch.leaves.len() as i32)
+        .fetch_one(&mut *db_tx)
+        .await?;
+
+        for leaf in &batch.leaves {
+            let proof: Vec<String> = leaf
+                .proof
+                .iter()
+                .map(|hash| format!("0x{}", hex::encode(hash)))
+                .collect();
+
+            sqlx::query(
+                r#"
+                INSERT INTO anchor_proofs (record_id, batch_id, leaf_index, leaf_hash, proof)
+                VALUES ($1, $2, $3, $4, $5)
+                ON CONFLICT (record_id) DO UPDATE
+                SET batch_id = EXCLUDED.batch_id, leaf_index = EXCLUDED.leaf_index,
+                    leaf_hash = EXCLUDED.leaf_hash, proof = EXCLUDED.proof
+                "#
+            )
+            .bind(leaf.record_id)
+            .bind(batch_id)
+            .bind(leaf.leaf_index as i32)
+            .bind(format!("0x{}", hex::encode(leaf.leaf_hash)))
+            .bind(serde_json::json!(proof))
+            .execute(&mut *db_tx)
+            .await?;
+        }
+
+        db_tx.commit().await?;
+
+        info!(
+            "Saved anchor batch {} with {} leaves (tx_hash: {})",
+            batch_id,
+            batch.leaves.len(),
+            batch.tx_hash
+        );
+
+        Ok(batch_id)
+    }
+
+    async fn find_proof(&self, record_id: i64) -> AppResult<Option<AnchorLeaf>> {
+        let row = sqlx::query(
+            "SELECT leaf_index, leaf_hash, proof FROM anchor_proofs WHERE record_id = $1"
+        )
+        .bind(record_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        let Some(row) = row else {
+            return Ok(None);
+        };
+
+        let proof: Vec<String> = serde_json::from_value(row.get("proof"))?;
+        let leaf_hash: String = row.get("leaf_hash");
+
+        Ok(Some(AnchorLeaf {
+            record_id,
+            leaf_index: row.get::<i32, _>("leaf_index") as usize,
+            leaf_hash: decode_hash(&leaf_hash)?,
+            proof: proof.iter().map(|hash| decode_hash(hash)).collect::<AppResult<_>>()?,
+        }))
+    }
+}
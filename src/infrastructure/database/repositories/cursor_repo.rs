@@ -0,0 +1,55 @@
+use crate::{error::AppResult, shared::traits::CursorStore};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// Persists a low watermark per worker: every committed `transactions` row at
+/// or below it has been handed off, so claims can start scanning just above it.
+/// Ids are assigned before commit, so a row that commits late can still land
+/// below the watermark; the workers' periodic full sweep picks those up.
+pub struct CursorRepository {
+    pool: PgPool,
+}
+
+impl CursorRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl CursorStore for CursorRepository {
+    async fn load_cursor(&self, worker_name: &str) -> AppResult<i64> {
+        let last_id: Option<i64> =
+            sqlx::query_scalar("SELECT last_id FROM worker_cursors WHERE worker_name = $1")
+                .bind(worker_name)
+                .fetch_optional(&self.pool)
+                .await?;
+
+        Ok(last_id.unwrap_or(0))
+    }
+
+    async fn advance_cursor(&self, worker_name: &str) -> AppResult<i64> {
+        // Stop just below the oldest row still pending, including rows leased
+        // by other replicas, or at the newest row when nothing is pending
+        let last_id: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO worker_cursors (worker_name, last_id)
+            SELECT $1, COALESCE(
+                (SELECT MIN(id)::BIGINT - 1 FROM transactions
+                 WHERE status = 'pending'
+                   AND id > COALESCE((SELECT last_id FROM worker_cursors WHERE worker_name = $1), 0)),
+                (SELECT MAX(id)::BIGINT FROM transactions),
+                0
+            )
+            ON CONFLICT (worker_name) DO UPDATE
+            SET last_id = GREATEST(worker_cursors.last_id, EXCLUDED.last_id), updated_at = NOW()
+            RETURNING last_id
+            "#
+        )
+        .bind(worker_name)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(last_id)
+    }
+}
@@ -0,0 +1,39 @@
+use crate::{domain::models::leadership::FencingToken, error::AppResult, shared::traits::LeaderTokenStore};
+use async_trait::async_trait;
+use sqlx::PgPool;
+
+/// Registers the fencing token of every new leader, which fenced writes
+/// compare against through `holds_leadership`
+pub struct LeaderTokenRepository {
+    pool: PgPool,
+}
+
+impl LeaderTokenRepository {
+    pub fn new(pool: PgPool) -> Self {
+        Self { pool }
+    }
+}
+
+#[async_trait]
+impl LeaderTokenStore for LeaderTokenRepository {
+    async fn register(&self, role: &str, election_token: u64) -> AppResult<FencingToken> {
+        // Kept strictly increasing even if the election's counter is ever reset
+        let token: i64 = sqlx::query_scalar(
+            r#"
+            INSERT INTO leader_tokens (role, token) VALUES ($1, $2)
+            ON CONFLICT (role) DO UPDATE
+            SET token = GREATEST(leader_tokens.token + 1, EXCLUDED.token), updated_at = CURRENT_TIMESTAMP
+            RETURNING token
+            "#
+        )
+        .bind(role)
+        .bind(i64::try_from(election_token).unwrap_or(i64::MAX))
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(FencingToken {
+            role: role.to_string(),
+            token,
+        })
+    }
+}
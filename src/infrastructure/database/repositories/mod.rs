@@ -1 +1,4 @@
-pub mod processed_jobs_repo; 
\ No newline at end of file
+pub mod anchor_repo;
+pub mod cursor_repo;
+pub mod leader_token_repo;
+pub mod processed_jobs_repo;
\ No newline at end of file
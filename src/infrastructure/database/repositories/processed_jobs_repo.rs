@@ -1,10 +1,17 @@
 use crate::{
-    error::AppResult,
+    domain::models::{
+        broadcast::{SentTransaction, SignedTransaction},
+        dead_letter::DeadLetter,
+        leadership::FencingToken,
+        processed_job::{ConfirmedJob, JobStatus, ProcessedJob, SentJob, StaleJob},
+        receipt::TransactionReceipt,
+    },
+    error::{AppError, AppResult},
     shared::traits::ProcessedJobsTracker as ProcessedJobsTrackerTrait,
 };
 use async_trait::async_trait;
-use sqlx::{PgPool, Row};
-use tracing::{debug, error, info};
+use sqlx::{postgres::PgRow, PgPool, Postgres, Row, Transaction};
+use tracing::{debug, error, info, warn};
 
 pub struct ProcessedJobsTracker {
     pool: PgPool,
@@ -15,29 +22,187 @@ impl ProcessedJobsTracker {
         Self { pool }
     }
 
-    pub async fn get_all_processed(&self) -> AppResult<Vec<(i64, Option<String>, String)>> {
-        let rows = sqlx::query(
-            "SELECT record_id, tx_hash, status FROM processed_jobs ORDER BY updated_at DESC"
+    async fn record_receipt(
+        &self,
+        record_id: i64,
+        receipt: &TransactionReceipt,
+        status: JobStatus,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<u64> {
+        let result = sqlx::query(
+            r#"
+            UPDATE processed_jobs
+            SET status = $9, tx_hash = $1, block_number = $2, block_hash = $3, gas_used = $4,
+                effective_gas_price = $5, updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $6 AND status IN ('broadcast', 'cancelling') AND holds_leadership($7, $8)
+            "#
         )
-        .fetch_all(&self.pool)
+        .bind(format!("0x{}", hex::encode(receipt.tx_hash)))
+        .bind(to_db_int(receipt.block_number as u128, "block_number")?)
+        .bind(format!("0x{}", hex::encode(receipt.block_hash)))
+        .bind(to_db_int(receipt.gas_used as u128, "gas_used")?)
+        .bind(to_db_int(receipt.effective_gas_price, "effective_gas_price")?)
+        .bind(record_id)
+        .bind(fence.map(|fence| fence.role.as_str()))
+        .bind(fence.map(|fence| fence.token))
+        .bind(status)
+        .execute(&self.pool)
         .await?;
 
-        let processed_jobs = rows
-            .into_iter()
-            .map(|row| {
-                let record_id: i64 = row.get("record_id");
-                let tx_hash: Option<String> = row.get("tx_hash");
-                let status: String = row.get("status");
-                (record_id, tx_hash, status)
-            })
-            .collect();
+        Ok(result.rows_affected())
+    }
+
+    async fn insert_job_transaction(
+        db_tx: &mut Transaction<'_, Postgres>,
+        record_id: i64,
+        sent: &SentTransaction,
+        cancellation: bool,
+    ) -> AppResult<()> {
+        sqlx::query(
+            r#"
+            INSERT INTO job_transactions (tx_hash, record_id, nonce, max_fee_per_gas, max_priority_fee_per_gas, cancellation)
+            VALUES ($1, $2, $3, $4, $5, $6)
+            ON CONFLICT (record_id, tx_hash) DO NOTHING
+            "#
+        )
+        .bind(sent.tx_hash_hex())
+        .bind(record_id)
+        .bind(to_db_int(sent.nonce as u128, "nonce")?)
+        .bind(to_db_int(sent.max_fee_per_gas, "max_fee_per_gas")?)
+        .bind(to_db_int(sent.max_priority_fee_per_gas, "max_priority_fee_per_gas")?)
+        .bind(cancellation)
+        .execute(&mut **db_tx)
+        .await?;
+
+        Ok(())
+    }
+
+    /// Parks a job in `dead_letters` with its failure history and a snapshot of its payload
+    async fn insert_dead_letter(
+        db_tx: &mut Transaction<'_, Postgres>,
+        record_id: i64,
+        attempts: i32,
+    ) -> AppResult<i64> {
+        let dead_letter_id = sqlx::query_scalar(
+            r#"
+            INSERT INTO dead_letters (record_id, attempts, errors, payload)
+            VALUES (
+                $1, $2,
+                COALESCE(
+                    (SELECT jsonb_agg(details ORDER BY id) FROM job_events
+                     WHERE record_id = $1 AND event_type = 'failed'),
+                    '[]'::jsonb
+                ),
+                (SELECT payload FROM transactions WHERE id = $1)
+            )
+            RETURNING id
+            "#
+        )
+        .bind(record_id)
+        .bind(attempts)
+        .fetch_one(&mut **db_tx)
+        .await?;
 
-        Ok(processed_jobs)
+        Ok(dead_letter_id)
+    }
+
+    async fn insert_failure_event(
+        db_tx: &mut Transaction<'_, Postgres>,
+        record_id: i64,
+        attempt: i32,
+        error: &str,
+    ) -> AppResult<()> {
+        let details = serde_json::json!({ "attempt": attempt, "error": error });
+        sqlx::query("INSERT INTO job_events (record_id, event_type, details) VALUES ($1, 'failed', $2)")
+            .bind(record_id)
+            .bind(details)
+            .execute(&mut **db_tx)
+            .await?;
+
+        Ok(())
+    }
+}
+
+const DEAD_LETTER_SELECT: &str = r#"
+    SELECT id, record_id, attempts, errors, payload, created_at, replayed_at
+    FROM dead_letters
+"#;
+
+fn dead_letter_from_row(row: &PgRow) -> DeadLetter {
+    DeadLetter {
+        id: row.get("id"),
+        record_id: row.get("record_id"),
+        attempts: row.get("attempts"),
+        errors: row.get("errors"),
+        payload: row.get("payload"),
+        created_at: row.get("created_at"),
+        replayed_at: row.get("replayed_at"),
     }
 }
 
+/// Selects sent jobs together with every transaction hash broadcast for them
+const SENT_JOB_SELECT: &str = r#"
+    SELECT p.record_id, p.tx_hash, p.nonce, p.raw_tx, p.max_fee_per_gas, p.max_priority_fee_per_gas,
+        ARRAY(SELECT t.tx_hash FROM job_transactions t WHERE t.record_id = p.record_id ORDER BY t.created_at DESC) AS tx_hashes,
+        ARRAY(SELECT t.tx_hash FROM job_transactions t WHERE t.record_id = p.record_id AND t.cancellation) AS cancellation_tx_hashes
+    FROM processed_jobs p
+"#;
+
+fn sent_job_from_row(row: &PgRow) -> SentJob {
+    let tx_hash: String = row.get("tx_hash");
+    let mut tx_hashes: Vec<String> = row.get("tx_hashes");
+    if !tx_hashes.contains(&tx_hash) {
+        tx_hashes.insert(0, tx_hash.clone());
+    }
+
+    SentJob {
+        record_id: row.get("record_id"),
+        tx_hash,
+        tx_hashes,
+        cancellation_tx_hashes: row.get("cancellation_tx_hashes"),
+        nonce: row.get("nonce"),
+        raw_tx: row.get("raw_tx"),
+        max_fee_per_gas: row.get("max_fee_per_gas"),
+        max_priority_fee_per_gas: row.get("max_priority_fee_per_gas"),
+    }
+}
+
+/// Converts a chain quantity into a BIGINT column value
+fn to_db_int(value: u128, column: &str) -> AppResult<i64> {
+    i64::try_from(value)
+        .map_err(|_| AppError::Internal(format!("{} value {} does not fit into BIGINT", column, value)))
+}
+
 #[async_trait]
 impl ProcessedJobsTrackerTrait for ProcessedJobsTracker {
+    /// Most recently updated jobs first, optionally only those in `status`
+    async fn get_all_processed(&self, status: Option<JobStatus>, limit: i64) -> AppResult<Vec<ProcessedJob>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT record_id, tx_hash, status, attempts, updated_at
+            FROM processed_jobs
+            WHERE $1::job_status IS NULL OR status = $1
+            ORDER BY updated_at DESC
+            LIMIT $2
+            "#
+        )
+        .bind(status)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .into_iter()
+            .map(|row| ProcessedJob {
+                record_id: row.get("record_id"),
+                tx_hash: row.get("tx_hash"),
+                status: row.get("status"),
+                attempts: row.get("attempts"),
+                updated_at: row.get("updated_at"),
+            })
+            .collect())
+    }
+
     async fn is_processed(&self, record_id: i64) -> AppResult<bool> {
         let row = sqlx::query("SELECT record_id FROM processed_jobs WHERE record_id = $1")
             .bind(record_id)
@@ -47,53 +212,1005 @@ impl ProcessedJobsTrackerTrait for ProcessedJobsTracker {
         Ok(row.is_some())
     }
 
-    async fn mark_pending(&self, record_id: i64) -> AppResult<()> {
+    /// Creates the job in `claimed`; false when any replica already created it
+    async fn claim(&self, record_id: i64) -> AppResult<bool> {
         let result = sqlx::query(
-            "INSERT INTO processed_jobs (record_id, status) VALUES ($1, 'pending') ON CONFLICT (record_id) DO NOTHING"
+            "INSERT INTO processed_jobs (record_id, status) VALUES ($1, 'claimed') ON CONFLICT (record_id) DO NOTHING"
         )
         .bind(record_id)
         .execute(&self.pool)
         .await?;
 
-        if result.rows_affected() > 0 {
-            debug!("Marked record {} as pending", record_id);
+        let inserted = result.rows_affected() > 0;
+        if inserted {
+            debug!("Claimed record {}", record_id);
         } else {
-            debug!("Record {} was already marked as pending", record_id);
+            debug!("Record {} was already claimed", record_id);
         }
 
-        Ok(())
+        Ok(inserted)
     }
 
-    async fn mark_sent(&self, record_id: i64, tx_hash: &str) -> AppResult<()> {
+    async fn mark_validated(&self, record_id: i64) -> AppResult<bool> {
         let result = sqlx::query(
-            "UPDATE processed_jobs SET tx_hash = $1, status = 'sent', updated_at = CURRENT_TIMESTAMP WHERE record_id = $2"
+            r#"
+            UPDATE processed_jobs SET status = 'validated', updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $1 AND status = 'claimed'
+            "#
         )
-        .bind(tx_hash)
+        .bind(record_id)
+        .execute(&self.pool)
+        .await?;
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Writes the signed transaction ahead of its broadcast, so a crash in
+    /// between leaves the exact bytes to rebroadcast instead of signing again
+    async fn mark_signed(&self, record_id: i64, signed: &SignedTransaction) -> AppResult<bool> {
+        let result = sqlx::query(
+            r#"
+            UPDATE processed_jobs
+            SET status = 'signed', tx_hash = $1, nonce = $2, raw_tx = $3, max_fee_per_gas = $4,
+                max_priority_fee_per_gas = $5, updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $6 AND status = 'validated'
+            "#
+        )
+        .bind(signed.tx_hash_hex())
+        .bind(to_db_int(signed.nonce as u128, "nonce")?)
+        .bind(&signed.raw)
+        .bind(to_db_int(signed.max_fee_per_gas, "max_fee_per_gas")?)
+        .bind(to_db_int(signed.max_priority_fee_per_gas, "max_priority_fee_per_gas")?)
         .bind(record_id)
         .execute(&self.pool)
         .await?;
 
         if result.rows_affected() > 0 {
-            info!("Marked record {} as sent with tx_hash: {}", record_id, tx_hash);
+            debug!("Record {} signed as {} at nonce {}", record_id, signed.tx_hash_hex(), signed.nonce);
+        }
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    async fn mark_broadcast(&self, record_id: i64, sent: &SentTransaction) -> AppResult<bool> {
+        let tx_hash = sent.tx_hash_hex();
+        let mut db_tx = self.pool.begin().await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE processed_jobs
+            SET tx_hash = $1, nonce = $2, status = 'broadcast', sent_at = CURRENT_TIMESTAMP,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $3 AND status = 'signed'
+            "#
+        )
+        .bind(&tx_hash)
+        .bind(to_db_int(sent.nonce as u128, "nonce")?)
+        .bind(record_id)
+        .execute(&mut *db_tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            error!("Record {} was no longer signed when recording broadcast {}", record_id, tx_hash);
+            return Ok(false);
+        }
+
+        Self::insert_job_transaction(&mut db_tx, record_id, sent, false).await?;
+        db_tx.commit().await?;
+
+        info!("Marked record {} as broadcast with tx_hash: {}", record_id, tx_hash);
+        Ok(true)
+    }
+
+    async fn mark_failed(
+        &self,
+        record_id: i64,
+        error: &str,
+        next_attempt_at: chrono::DateTime<chrono::Utc>,
+    ) -> AppResult<()> {
+        let mut db_tx = self.pool.begin().await?;
+
+        let attempts: Option<i32> = sqlx::query_scalar(
+            r#"
+            UPDATE processed_jobs
+            SET status = 'failed', attempts = attempts + 1, last_error = $2, next_attempt_at = $3,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $1 AND status IN ('claimed', 'validated', 'signed')
+            RETURNING attempts
+            "#
+        )
+        .bind(record_id)
+        .bind(error)
+        .bind(next_attempt_at)
+        .fetch_optional(&mut *db_tx)
+        .await?;
+
+        let Some(attempts) = attempts else {
+            return Ok(());
+        };
+        Self::insert_failure_event(&mut db_tx, record_id, attempts, error).await?;
+        db_tx.commit().await?;
+
+        error!(
+            "Marked record {} as failed (attempt {}), retrying at {}",
+            record_id,
+            attempts,
+            next_attempt_at.to_rfc3339()
+        );
+        Ok(())
+    }
+
+    /// Records the final failure of a job and parks it in `dead_letters` with
+    /// its failure history and a snapshot of its payload. Jobs that already got
+    /// past sending are left alone.
+    async fn dead_letter(&self, record_id: i64, error: &str) -> AppResult<Option<i64>> {
+        let mut db_tx = self.pool.begin().await?;
+
+        let attempts: Option<i32> = sqlx::query_scalar(
+            r#"
+            INSERT INTO processed_jobs (record_id, status, attempts, last_error)
+            VALUES ($1, 'dead_lettered', 1, $2)
+            ON CONFLICT (record_id) DO UPDATE
+            SET status = 'dead_lettered', attempts = processed_jobs.attempts + 1, last_error = EXCLUDED.last_error,
+                next_attempt_at = NULL, updated_at = CURRENT_TIMESTAMP
+            WHERE processed_jobs.status IN ('claimed', 'validated', 'signed', 'failed')
+            RETURNING attempts
+            "#
+        )
+        .bind(record_id)
+        .bind(error)
+        .fetch_optional(&mut *db_tx)
+        .await?;
+
+        let Some(attempts) = attempts else {
+            warn!("Record {} is past sending, not dead-lettering it", record_id);
+            return Ok(None);
+        };
+        Self::insert_failure_event(&mut db_tx, record_id, attempts, error).await?;
+        let dead_letter_id = Self::insert_dead_letter(&mut db_tx, record_id, attempts).await?;
+
+        db_tx.commit().await?;
+
+        error!(
+            "Dead-lettered record {} after {} attempts as {}: {}",
+            record_id, attempts, dead_letter_id, error
+        );
+        Ok(Some(dead_letter_id))
+    }
+
+    async fn fetch_dead_letters(&self, include_replayed: bool, limit: i64) -> AppResult<Vec<DeadLetter>> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE $1 OR replayed_at IS NULL ORDER BY id DESC LIMIT $2",
+            DEAD_LETTER_SELECT
+        ))
+        .bind(include_replayed)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(dead_letter_from_row).collect())
+    }
+
+    async fn find_dead_letter(&self, id: i64) -> AppResult<Option<DeadLetter>> {
+        let row = sqlx::query(&format!("{} WHERE id = $1", DEAD_LETTER_SELECT))
+            .bind(id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.as_ref().map(dead_letter_from_row))
+    }
+
+    /// Requeues a dead-lettered job as a failed job that is due immediately,
+    /// with a fresh attempt budget
+    async fn replay_dead_letter(&self, id: i64) -> AppResult<bool> {
+        let mut db_tx = self.pool.begin().await?;
+
+        let record_id: Option<i64> = sqlx::query_scalar(
+            "UPDATE dead_letters SET replayed_at = CURRENT_TIMESTAMP WHERE id = $1 AND replayed_at IS NULL RETURNING record_id"
+        )
+        .bind(id)
+        .fetch_optional(&mut *db_tx)
+        .await?;
+
+        let Some(record_id) = record_id else {
+            return Ok(false);
+        };
+
+        let result = sqlx::query(
+            r#"
+            UPDATE processed_jobs
+            SET status = 'failed', attempts = 0, next_attempt_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $1 AND status = 'dead_lettered'
+            "#
+        )
+        .bind(record_id)
+        .execute(&mut *db_tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Ok(false);
+        }
+
+        sqlx::query("INSERT INTO job_events (record_id, event_type, details) VALUES ($1, 'replayed', $2)")
+            .bind(record_id)
+            .bind(serde_json::json!({ "dead_letter_id": id }))
+            .execute(&mut *db_tx)
+            .await?;
+
+        db_tx.commit().await?;
+
+        info!("Replayed dead letter {} for record {}", id, record_id);
+        Ok(true)
+    }
+
+    async fn fetch_retryable_jobs(&self, limit: i64) -> AppResult<Vec<i64>> {
+        let record_ids = sqlx::query_scalar(
+            r#"
+            SELECT record_id FROM processed_jobs
+            WHERE status = 'failed' AND next_attempt_at <= NOW()
+            ORDER BY next_attempt_at
+            LIMIT $1
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(record_ids)
+    }
+
+    /// Moves a due failed job back to claimed, returning its attempts so far.
+    /// Only one caller wins when several replicas pick up the same job.
+    async fn claim_retry(&self, record_id: i64) -> AppResult<Option<i32>> {
+        let attempts = sqlx::query_scalar(
+            r#"
+            UPDATE processed_jobs
+            SET status = 'claimed', next_attempt_at = NULL, updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $1 AND status = 'failed' AND next_attempt_at <= NOW()
+            RETURNING attempts
+            "#
+        )
+        .bind(record_id)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        Ok(attempts)
+    }
+
+    async fn fetch_stale_jobs(
+        &self,
+        updated_before: chrono::DateTime<chrono::Utc>,
+        limit: i64,
+    ) -> AppResult<Vec<StaleJob>> {
+        let rows = sqlx::query(
+            r#"
+            SELECT record_id, status, updated_at, tx_hash, nonce, raw_tx, max_fee_per_gas, max_priority_fee_per_gas
+            FROM processed_jobs
+            WHERE status IN ('claimed', 'validated', 'signed') AND updated_at < $1
+            ORDER BY updated_at
+            LIMIT $2
+            "#
+        )
+        .bind(updated_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows
+            .iter()
+            .map(|row| StaleJob {
+                record_id: row.get("record_id"),
+                status: row.get("status"),
+                updated_at: row.get("updated_at"),
+                tx_hash: row.get("tx_hash"),
+                nonce: row.get("nonce"),
+                raw_tx: row.get("raw_tx"),
+                max_fee_per_gas: row.get("max_fee_per_gas"),
+                max_priority_fee_per_gas: row.get("max_priority_fee_per_gas"),
+            })
+            .collect())
+    }
+
+    /// Fails a stale job so the retry worker picks it up right away. Guarded by
+    /// the state and timestamp it was fetched with, so a job that made progress
+    /// since is left alone.
+    async fn requeue_stale(&self, job: &StaleJob, reason: &str, fence: Option<&FencingToken>) -> AppResult<bool> {
+        let mut db_tx = self.pool.begin().await?;
+
+        let attempts: Option<i32> = sqlx::query_scalar(
+            r#"
+            UPDATE processed_jobs
+            SET status = 'failed', attempts = attempts + 1, last_error = $3, next_attempt_at = CURRENT_TIMESTAMP,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $1 AND status = $2 AND updated_at = $4 AND holds_leadership($5, $6)
+            RETURNING attempts
+            "#
+        )
+        .bind(job.record_id)
+        .bind(job.status)
+        .bind(reason)
+        .bind(job.updated_at)
+        .bind(fence.map(|fence| fence.role.as_str()))
+        .bind(fence.map(|fence| fence.token))
+        .fetch_optional(&mut *db_tx)
+        .await?;
+
+        let Some(attempts) = attempts else {
+            return Ok(false);
+        };
+        Self::insert_failure_event(&mut db_tx, job.record_id, attempts, reason).await?;
+        db_tx.commit().await?;
+
+        warn!("Requeued stale record {} from {}: {}", job.record_id, job.status, reason);
+        Ok(true)
+    }
+
+    /// Sent jobs whose receipt was looked for least recently, marked as checked
+    /// now so that jobs that are never mined cannot hold back the rest
+    async fn fetch_sent_jobs(&self, limit: i64) -> AppResult<Vec<SentJob>> {
+        let record_ids: Vec<i64> = sqlx::query_scalar(
+            r#"
+            UPDATE processed_jobs SET checked_at = CURRENT_TIMESTAMP
+            WHERE record_id IN (
+                SELECT record_id FROM processed_jobs
+                WHERE status IN ('broadcast', 'cancelling') AND tx_hash IS NOT NULL
+                ORDER BY checked_at NULLS FIRST, updated_at
+                LIMIT $1
+            )
+            RETURNING record_id
+            "#
+        )
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let rows = sqlx::query(&format!(
+            "{} WHERE p.record_id = ANY($1) AND p.status IN ('broadcast', 'cancelling') AND p.tx_hash IS NOT NULL ORDER BY p.record_id",
+            SENT_JOB_SELECT
+        ))
+        .bind(&record_ids)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(sent_job_from_row).collect())
+    }
+
+    async fn fetch_stuck_jobs(&self, sent_before: chrono::DateTime<chrono::Utc>, limit: i64) -> AppResult<Vec<SentJob>> {
+        let rows = sqlx::query(&format!(
+            "{} WHERE p.status IN ('broadcast', 'cancelling') AND p.tx_hash IS NOT NULL AND p.sent_at < $1 ORDER BY p.sent_at LIMIT $2",
+            SENT_JOB_SELECT
+        ))
+        .bind(sent_before)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        Ok(rows.iter().map(sent_job_from_row).collect())
+    }
+
+    /// Writes a signed replacement ahead of its broadcast for every sent job
+    /// still pointing at `replaced_tx_hash`, since an anchoring transaction
+    /// covers a whole batch of jobs. The replacement of a cancellation is a
+    /// cancellation too. Returns false when no job is left or leadership was
+    /// lost, in which case the replacement must not be broadcast.
+    async fn record_replacement(
+        &self,
+        replaced_tx_hash: &str,
+        signed: &SignedTransaction,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<bool> {
+        let tx_hash = signed.tx_hash_hex();
+        let sent = &signed.sent();
+        let mut db_tx = self.pool.begin().await?;
+
+        let record_ids: Vec<i64> = sqlx::query_scalar(
+            r#"
+            UPDATE processed_jobs
+            SET tx_hash = $1, raw_tx = $2, max_fee_per_gas = $3, max_priority_fee_per_gas = $4,
+                sent_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+            WHERE tx_hash = $5 AND status IN ('broadcast', 'cancelling') AND holds_leadership($6, $7)
+            RETURNING record_id
+            "#
+        )
+        .bind(&tx_hash)
+        .bind(&signed.raw)
+        .bind(to_db_int(signed.max_fee_per_gas, "max_fee_per_gas")?)
+        .bind(to_db_int(signed.max_priority_fee_per_gas, "max_priority_fee_per_gas")?)
+        .bind(replaced_tx_hash)
+        .bind(fence.map(|fence| fence.role.as_str()))
+        .bind(fence.map(|fence| fence.token))
+        .fetch_all(&mut *db_tx)
+        .await?;
+
+        if record_ids.is_empty() {
+            warn!(
+                "No sent jobs left for {}, or leadership was lost, when recording replacement {}",
+                replaced_tx_hash, tx_hash
+            );
+            return Ok(false);
+        }
+
+        let cancellation: bool = sqlx::query_scalar(
+            "SELECT EXISTS (SELECT 1 FROM job_transactions WHERE tx_hash = $1 AND cancellation)"
+        )
+        .bind(replaced_tx_hash)
+        .fetch_one(&mut *db_tx)
+        .await?;
+
+        let details = serde_json::json!({
+            "replaced_tx_hash": replaced_tx_hash,
+            "tx_hash": tx_hash,
+            "nonce": sent.nonce,
+            "max_fee_per_gas": sent.max_fee_per_gas.to_string(),
+            "max_priority_fee_per_gas": sent.max_priority_fee_per_gas.to_string(),
+        });
+
+        for record_id in &record_ids {
+            Self::insert_job_transaction(&mut db_tx, *record_id, sent, cancellation).await?;
+            sqlx::query("INSERT INTO job_events (record_id, event_type, details) VALUES ($1, 'fee_bump', $2)")
+                .bind(record_id)
+                .bind(&details)
+                .execute(&mut *db_tx)
+                .await?;
+        }
+
+        db_tx.commit().await?;
+
+        info!("Records {:?} replaced {} with {}", record_ids, replaced_tx_hash, tx_hash);
+        Ok(true)
+    }
+
+    async fn mark_confirmed(
+        &self,
+        record_id: i64,
+        receipt: &TransactionReceipt,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<()> {
+        if self.record_receipt(record_id, receipt, JobStatus::Confirmed, fence).await? > 0 {
+            info!(
+                "Marked record {} as confirmed in block {}",
+                record_id, receipt.block_number
+            );
         } else {
-            error!("Failed to update record {} as sent", record_id);
+            warn!("Record {} was no longer sent, or leadership was lost, when confirming", record_id);
         }
 
         Ok(())
     }
 
-    async fn mark_failed(&self, record_id: i64) -> AppResult<()> {
+    /// Records that the cancellation of a job was mined, so its own transaction never will be
+    async fn mark_cancelled(
+        &self,
+        record_id: i64,
+        receipt: &TransactionReceipt,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<()> {
+        if self.record_receipt(record_id, receipt, JobStatus::Cancelled, fence).await? > 0 {
+            info!(
+                "Marked record {} as cancelled by 0x{} in block {}",
+                record_id,
+                hex::encode(receipt.tx_hash),
+                receipt.block_number
+            );
+        } else {
+            warn!("Record {} was no longer cancelling, or leadership was lost, when cancelling", record_id);
+        }
+
+        Ok(())
+    }
+
+    /// Dead-letters a job whose transaction was mined but reverted. The nonce
+    /// and fees are spent, and running it again would revert the same way, so
+    /// it is not retried; the receipt is kept as its final error.
+    async fn mark_reverted(
+        &self,
+        record_id: i64,
+        receipt: &TransactionReceipt,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<()> {
+        let tx_hash = format!("0x{}", hex::encode(receipt.tx_hash));
+        let block_hash = format!("0x{}", hex::encode(receipt.block_hash));
+        let error = format!(
+            "transaction {} reverted in block {} ({}) after using {} gas",
+            tx_hash, receipt.block_number, block_hash, receipt.gas_used
+        );
+        let mut db_tx = self.pool.begin().await?;
+
+        let attempts: Option<i32> = sqlx::query_scalar(
+            r#"
+            UPDATE processed_jobs
+            SET status = 'dead_lettered', tx_hash = $1, block_number = $2, block_hash = $3, gas_used = $4,
+                effective_gas_price = $5, attempts = attempts + 1, last_error = $6, next_attempt_at = NULL,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $7 AND status IN ('broadcast', 'cancelling') AND holds_leadership($8, $9)
+            RETURNING attempts
+            "#
+        )
+        .bind(&tx_hash)
+        .bind(to_db_int(receipt.block_number as u128, "block_number")?)
+        .bind(&block_hash)
+        .bind(to_db_int(receipt.gas_used as u128, "gas_used")?)
+        .bind(to_db_int(receipt.effective_gas_price, "effective_gas_price")?)
+        .bind(&error)
+        .bind(record_id)
+        .bind(fence.map(|fence| fence.role.as_str()))
+        .bind(fence.map(|fence| fence.token))
+        .fetch_optional(&mut *db_tx)
+        .await?;
+
+        let Some(attempts) = attempts else {
+            warn!("Record {} was no longer sent, or leadership was lost, when recording revert", record_id);
+            return Ok(());
+        };
+        Self::insert_failure_event(&mut db_tx, record_id, attempts, &error).await?;
+        let dead_letter_id = Self::insert_dead_letter(&mut db_tx, record_id, attempts).await?;
+
+        db_tx.commit().await?;
+
+        error!("Dead-lettered record {} as {}: {}", record_id, dead_letter_id, error);
+        Ok(())
+    }
+
+    /// Confirmed jobs, and jobs cancelled by a mined cancellation, in the
+    /// newest `limit` blocks at or above `min_block`. The
+    /// limit counts blocks rather than jobs, so a busy block cannot crowd out
+    /// the recent ones, where reorgs are most likely.
+    async fn fetch_confirmed_since(&self, min_block: u64, limit: i64) -> AppResult<Vec<ConfirmedJob>> {
+        let rows = sqlx::query(
+            r#"
+            WITH blocks AS (
+                SELECT DISTINCT block_number, block_hash
+                FROM processed_jobs
+                WHERE status IN ('confirmed', 'cancelled') AND block_number >= $1 AND block_hash IS NOT NULL
+                ORDER BY block_number DESC
+                LIMIT $2
+            )
+            SELECT record_id, tx_hash, block_number, block_hash
+            FROM processed_jobs
+            JOIN blocks USING (block_number, block_hash)
+            WHERE status IN ('confirmed', 'cancelled')
+            ORDER BY block_number DESC, record_id
+            "#
+        )
+        .bind(to_db_int(min_block as u128, "block_number")?)
+        .bind(limit)
+        .fetch_all(&self.pool)
+        .await?;
+
+        let confirmed_jobs = rows
+            .into_iter()
+            .map(|row| ConfirmedJob {
+                record_id: row.get("record_id"),
+                tx_hash: row.get("tx_hash"),
+                block_number: row.get("block_number"),
+                block_hash: row.get("block_hash"),
+            })
+            .collect();
+
+        Ok(confirmed_jobs)
+    }
+
+    async fn mark_reorged(
+        &self,
+        job: &ConfirmedJob,
+        canonical_block_hash: Option<[u8; 32]>,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<()> {
+        let mut db_tx = self.pool.begin().await?;
+
+        let status: Option<JobStatus> = sqlx::query_scalar(
+            r#"
+            UPDATE processed_jobs
+            SET status = CASE status WHEN 'cancelled' THEN 'cancelling'::job_status ELSE 'broadcast'::job_status END,
+                block_number = NULL, block_hash = NULL, gas_used = NULL, effective_gas_price = NULL,
+                updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $1 AND status IN ('confirmed', 'cancelled') AND block_hash = $2
+                AND holds_leadership($3, $4)
+            RETURNING status
+            "#
+        )
+        .bind(job.record_id)
+        .bind(&job.block_hash)
+        .bind(fence.map(|fence| fence.role.as_str()))
+        .bind(fence.map(|fence| fence.token))
+        .fetch_optional(&mut *db_tx)
+        .await?;
+
+        let Some(status) = status else {
+            debug!("Record {} changed, or leadership was lost, before reorg could be recorded", job.record_id);
+            return Ok(());
+        };
+
+        let details = serde_json::json!({
+            "tx_hash": job.tx_hash,
+            "orphaned_block_number": job.block_number,
+            "orphaned_block_hash": job.block_hash,
+            "canonical_block_hash": canonical_block_hash.map(|hash| format!("0x{}", hex::encode(hash))),
+        });
+        sqlx::query("INSERT INTO job_events (record_id, event_type, details) VALUES ($1, 'reorg', $2)")
+            .bind(job.record_id)
+            .bind(details)
+            .execute(&mut *db_tx)
+            .await?;
+
+        db_tx.commit().await?;
+
+        warn!(
+            "Record {} was orphaned by a reorg at block {}; moved back to {}",
+            job.record_id, job.block_number, status
+        );
+
+        Ok(())
+    }
+
+    async fn get_status(&self, record_id: i64) -> AppResult<Option<(JobStatus, Option<String>)>> {
+        let row = sqlx::query("SELECT status, tx_hash FROM processed_jobs WHERE record_id = $1")
+            .bind(record_id)
+            .fetch_optional(&self.pool)
+            .await?;
+
+        Ok(row.map(|row| (row.get("status"), row.get("tx_hash"))))
+    }
+
+    async fn cancel_unbroadcast(&self, record_id: i64) -> AppResult<bool> {
         let result = sqlx::query(
-            "UPDATE processed_jobs SET status = 'failed', updated_at = CURRENT_TIMESTAMP WHERE record_id = $1"
+            r#"
+            INSERT INTO processed_jobs (record_id, status) VALUES ($1, 'cancelled')
+            ON CONFLICT (record_id) DO UPDATE
+            SET status = 'cancelled', next_attempt_at = NULL, updated_at = CURRENT_TIMESTAMP
+            WHERE processed_jobs.status IN ('claimed', 'validated', 'failed')
+            "#
         )
         .bind(record_id)
         .execute(&self.pool)
         .await?;
 
         if result.rows_affected() > 0 {
-            error!("Marked record {} as failed", record_id);
+            info!("Cancelled record {} before broadcast", record_id);
+        }
+
+        Ok(result.rows_affected() > 0)
+    }
+
+    /// Cancels a job that was signed but never broadcast, returning the nonce
+    /// its transaction reserved so the caller can release it
+    async fn cancel_signed(&self, record_id: i64, tx_hash: &str) -> AppResult<Option<i64>> {
+        let nonce: Option<Option<i64>> = sqlx::query_scalar(
+            r#"
+            UPDATE processed_jobs
+            SET status = 'cancelled', updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $1 AND status = 'signed' AND tx_hash = $2
+            RETURNING nonce
+            "#
+        )
+        .bind(record_id)
+        .bind(tx_hash)
+        .fetch_optional(&self.pool)
+        .await?;
+
+        if nonce.is_some() {
+            info!("Cancelled signed record {} before broadcasting {}", record_id, tx_hash);
+        }
+
+        Ok(nonce.flatten())
+    }
+
+    /// Jobs still waiting on `tx_hash`, which is more than one for an anchored batch
+    async fn count_jobs_sharing(&self, tx_hash: &str) -> AppResult<i64> {
+        let count = sqlx::query_scalar(
+            "SELECT COUNT(*) FROM processed_jobs WHERE tx_hash = $1 AND status IN ('signed', 'broadcast')"
+        )
+        .bind(tx_hash)
+        .fetch_one(&self.pool)
+        .await?;
+
+        Ok(count)
+    }
+
+    /// Writes a signed cancellation ahead of its broadcast. The job stays
+    /// cancelling, watched for both transactions, until one of them is mined.
+    async fn mark_cancelling(
+        &self,
+        record_id: i64,
+        replaced_tx_hash: &str,
+        signed: &SignedTransaction,
+    ) -> AppResult<()> {
+        let tx_hash = signed.tx_hash_hex();
+        let sent = &signed.sent();
+        let mut db_tx = self.pool.begin().await?;
+
+        // A signed job has not recorded its own transaction yet, and it may still be mined
+        sqlx::query(
+            r#"
+            INSERT INTO job_transactions (tx_hash, record_id, nonce, max_fee_per_gas, max_priority_fee_per_gas)
+            SELECT tx_hash, record_id, nonce, COALESCE(max_fee_per_gas, 0), COALESCE(max_priority_fee_per_gas, 0)
+            FROM processed_jobs
+            WHERE record_id = $1 AND status = 'signed' AND tx_hash = $2 AND nonce IS NOT NULL
+            ON CONFLICT (record_id, tx_hash) DO NOTHING
+            "#
+        )
+        .bind(record_id)
+        .bind(replaced_tx_hash)
+        .execute(&mut *db_tx)
+        .await?;
+
+        let result = sqlx::query(
+            r#"
+            UPDATE processed_jobs
+            SET status = 'cancelling', tx_hash = $1, raw_tx = $2, max_fee_per_gas = $3,
+                max_priority_fee_per_gas = $4, sent_at = CURRENT_TIMESTAMP, updated_at = CURRENT_TIMESTAMP
+            WHERE record_id = $5 AND status IN ('signed', 'broadcast') AND tx_hash = $6
+            "#
+        )
+        .bind(&tx_hash)
+        .bind(&signed.raw)
+        .bind(to_db_int(signed.max_fee_per_gas, "max_fee_per_gas")?)
+        .bind(to_db_int(signed.max_priority_fee_per_gas, "max_priority_fee_per_gas")?)
+        .bind(record_id)
+        .bind(replaced_tx_hash)
+        .execute(&mut *db_tx)
+        .await?;
+
+        if result.rows_affected() == 0 {
+            return Err(AppError::Conflict(format!(
+                "Record {} moved on from {} while it was being cancelled",
+                record_id, replaced_tx_hash
+            )));
         }
 
+        Self::insert_job_transaction(&mut db_tx, record_id, sent, true).await?;
+
+        let details = serde_json::json!({
+            "cancelled_tx_hash": replaced_tx_hash,
+            "tx_hash": tx_hash,
+            "nonce": sent.nonce,
+        });
+        sqlx::query("INSERT INTO job_events (record_id, event_type, details) VALUES ($1, 'cancelled', $2)")
+            .bind(record_id)
+            .bind(details)
+            .execute(&mut *db_tx)
+            .await?;
+
+        db_tx.commit().await?;
+
+        info!("Cancelling record {} by replacing {} with {}", record_id, replaced_tx_hash, tx_hash);
         Ok(())
     }
-} 
\ No newline at end of file
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::infrastructure::database::connection::test_pool;
+
+    fn signed(byte: u8, nonce: u64) -> SignedTransaction {
+        SignedTransaction {
+            tx_hash: [byte; 32],
+            nonce,
+            max_fee_per_gas: 100,
+            max_priority_fee_per_gas: 2,
+            raw: vec![byte],
+        }
+    }
+
+    fn receipt(tx_hash: [u8; 32], block_number: u64) -> TransactionReceipt {
+        TransactionReceipt {
+            tx_hash,
+            block_number,
+            block_hash: [block_number as u8; 32],
+            gas_used: 21_000,
+            effective_gas_price: 50,
+            success: true,
+        }
+    }
+
+    async fn sign_job(tracker: &ProcessedJobsTracker, record_id: i64, signed: &SignedTransaction) {
+        assert!(tracker.claim(record_id).await.unwrap());
+        assert!(tracker.mark_validated(record_id).await.unwrap());
+        assert!(tracker.mark_signed(record_id, signed).await.unwrap());
+    }
+
+    async fn broadcast_job(tracker: &ProcessedJobsTracker, record_id: i64, signed: &SignedTransaction) {
+        sign_job(tracker, record_id, signed).await;
+        assert!(tracker.mark_broadcast(record_id, &signed.sent()).await.unwrap());
+    }
+
+    async fn status(tracker: &ProcessedJobsTracker, record_id: i64) -> JobStatus {
+        tracker.get_status(record_id).await.unwrap().unwrap().0
+    }
+
+    #[tokio::test]
+    async fn cancel_unbroadcast_drops_only_jobs_that_never_reached_the_node() {
+        let tracker = ProcessedJobsTracker::new(test_pool("cancel_unbroadcast").await);
+        tracker.claim(1).await.unwrap();
+        broadcast_job(&tracker, 2, &signed(2, 0)).await;
+
+        assert!(tracker.cancel_unbroadcast(1).await.unwrap());
+        assert!(tracker.cancel_unbroadcast(3).await.unwrap());
+        assert!(!tracker.cancel_unbroadcast(2).await.unwrap());
+
+        assert_eq!(status(&tracker, 1).await, JobStatus::Cancelled);
+        assert_eq!(status(&tracker, 3).await, JobStatus::Cancelled);
+        assert_eq!(status(&tracker, 2).await, JobStatus::Broadcast);
+    }
+
+    #[tokio::test]
+    async fn cancel_signed_returns_the_nonce_of_the_current_transaction_only() {
+        let tracker = ProcessedJobsTracker::new(test_pool("cancel_signed").await);
+        let original = signed(1, 7);
+        sign_job(&tracker, 1, &original).await;
+
+        assert_eq!(tracker.cancel_signed(1, &signed(9, 7).tx_hash_hex()).await.unwrap(), None);
+        assert_eq!(tracker.cancel_signed(1, &original.tx_hash_hex()).await.unwrap(), Some(7));
+        assert_eq!(status(&tracker, 1).await, JobStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn cancelling_jobs_are_watched_for_both_transactions() {
+        let tracker = ProcessedJobsTracker::new(test_pool("cancelling_watched").await);
+        let (original, cancellation) = (signed(1, 0), signed(2, 0));
+        broadcast_job(&tracker, 1, &original).await;
+
+        tracker
+            .mark_cancelling(1, &original.tx_hash_hex(), &cancellation)
+            .await
+            .unwrap();
+
+        assert_eq!(status(&tracker, 1).await, JobStatus::Cancelling);
+        let sent_jobs = tracker.fetch_sent_jobs(10).await.unwrap();
+        assert_eq!(sent_jobs.len(), 1);
+        assert_eq!(sent_jobs[0].tx_hash, cancellation.tx_hash_hex());
+        assert!(sent_jobs[0].tx_hashes.contains(&original.tx_hash_hex()));
+        assert!(sent_jobs[0].is_cancellation(&cancellation.tx_hash_hex()));
+        assert!(!sent_jobs[0].is_cancellation(&original.tx_hash_hex()));
+        assert_eq!(sent_jobs[0].signed_transaction().unwrap().unwrap().raw, cancellation.raw);
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_signed_job_keeps_its_own_transaction() {
+        let tracker = ProcessedJobsTracker::new(test_pool("cancelling_signed").await);
+        let (original, cancellation) = (signed(1, 0), signed(2, 0));
+        sign_job(&tracker, 1, &original).await;
+
+        tracker
+            .mark_cancelling(1, &original.tx_hash_hex(), &cancellation)
+            .await
+            .unwrap();
+
+        let sent_jobs = tracker.fetch_sent_jobs(10).await.unwrap();
+        assert_eq!(sent_jobs[0].tx_hashes.len(), 2);
+        assert!(sent_jobs[0].tx_hashes.contains(&original.tx_hash_hex()));
+    }
+
+    #[tokio::test]
+    async fn cancelling_a_job_that_moved_on_is_a_conflict() {
+        let tracker = ProcessedJobsTracker::new(test_pool("cancelling_conflict").await);
+        let original = signed(1, 0);
+        broadcast_job(&tracker, 1, &original).await;
+        tracker
+            .mark_cancelling(1, &original.tx_hash_hex(), &signed(2, 0))
+            .await
+            .unwrap();
+
+        let result = tracker
+            .mark_cancelling(1, &original.tx_hash_hex(), &signed(3, 0))
+            .await;
+
+        assert!(matches!(result, Err(AppError::Conflict(_))));
+        let sent_jobs = tracker.fetch_sent_jobs(10).await.unwrap();
+        assert_eq!(sent_jobs[0].tx_hash, signed(2, 0).tx_hash_hex());
+    }
+
+    #[tokio::test]
+    async fn the_mined_transaction_decides_between_cancelled_and_confirmed() {
+        let tracker = ProcessedJobsTracker::new(test_pool("cancelling_settled").await);
+        for record_id in [1, 2] {
+            let original = signed(record_id as u8, record_id as u64);
+            broadcast_job(&tracker, record_id, &original).await;
+            tracker
+                .mark_cancelling(record_id, &original.tx_hash_hex(), &signed(10 + record_id as u8, record_id as u64))
+                .await
+                .unwrap();
+        }
+
+        tracker.mark_cancelled(1, &receipt([11; 32], 5), None).await.unwrap();
+        tracker.mark_confirmed(2, &receipt([2; 32], 5), None).await.unwrap();
+
+        assert_eq!(
+            tracker.get_status(1).await.unwrap(),
+            Some((JobStatus::Cancelled, Some(signed(11, 1).tx_hash_hex())))
+        );
+        assert_eq!(
+            tracker.get_status(2).await.unwrap(),
+            Some((JobStatus::Confirmed, Some(signed(2, 2).tx_hash_hex())))
+        );
+    }
+
+    #[tokio::test]
+    async fn a_reorged_cancellation_goes_back_to_cancelling() {
+        let tracker = ProcessedJobsTracker::new(test_pool("cancelling_reorged").await);
+        let original = signed(1, 0);
+        broadcast_job(&tracker, 1, &original).await;
+        tracker
+            .mark_cancelling(1, &original.tx_hash_hex(), &signed(2, 0))
+            .await
+            .unwrap();
+        tracker.mark_cancelled(1, &receipt([2; 32], 5), None).await.unwrap();
+
+        let confirmed_jobs = tracker.fetch_confirmed_since(0, 10).await.unwrap();
+        assert_eq!(confirmed_jobs.len(), 1);
+        tracker.mark_reorged(&confirmed_jobs[0], None, None).await.unwrap();
+
+        assert_eq!(status(&tracker, 1).await, JobStatus::Cancelling);
+    }
+
+    #[tokio::test]
+    async fn a_bumped_cancellation_is_still_a_cancellation() {
+        let tracker = ProcessedJobsTracker::new(test_pool("cancelling_bumped").await);
+        let (original, cancellation, bumped) = (signed(1, 0), signed(2, 0), signed(3, 0));
+        broadcast_job(&tracker, 1, &original).await;
+        tracker
+            .mark_cancelling(1, &original.tx_hash_hex(), &cancellation)
+            .await
+            .unwrap();
+
+        assert!(tracker
+            .record_replacement(&cancellation.tx_hash_hex(), &bumped, None)
+            .await
+            .unwrap());
+
+        let sent_jobs = tracker.fetch_sent_jobs(10).await.unwrap();
+        assert_eq!(sent_jobs[0].tx_hash, bumped.tx_hash_hex());
+        assert!(sent_jobs[0].is_cancellation(&bumped.tx_hash_hex()));
+        assert!(!sent_jobs[0].is_cancellation(&original.tx_hash_hex()));
+    }
+
+    #[tokio::test]
+    async fn each_transition_only_leaves_its_own_state() {
+        let tracker = ProcessedJobsTracker::new(test_pool("guarded_transitions").await);
+        let transaction = signed(1, 0);
+
+        assert!(tracker.claim(1).await.unwrap());
+        assert!(!tracker.claim(1).await.unwrap());
+        assert!(!tracker.mark_signed(1, &transaction).await.unwrap());
+        assert!(!tracker.mark_broadcast(1, &transaction.sent()).await.unwrap());
+
+        assert!(tracker.mark_validated(1).await.unwrap());
+        assert!(!tracker.mark_validated(1).await.unwrap());
+        assert!(tracker.mark_signed(1, &transaction).await.unwrap());
+        assert!(!tracker.mark_signed(1, &signed(2, 1)).await.unwrap());
+        assert!(tracker.mark_broadcast(1, &transaction.sent()).await.unwrap());
+        assert!(!tracker.mark_broadcast(1, &transaction.sent()).await.unwrap());
+
+        assert_eq!(
+            tracker.get_status(1).await.unwrap(),
+            Some((JobStatus::Broadcast, Some(transaction.tx_hash_hex())))
+        );
+    }
+
+    #[tokio::test]
+    async fn a_job_cancelled_mid_run_is_left_alone() {
+        let tracker = ProcessedJobsTracker::new(test_pool("guarded_cancelled").await);
+        tracker.claim(1).await.unwrap();
+        tracker.cancel_unbroadcast(1).await.unwrap();
+
+        assert!(!tracker.mark_validated(1).await.unwrap());
+        assert!(!tracker.mark_signed(1, &signed(1, 0)).await.unwrap());
+        assert_eq!(status(&tracker, 1).await, JobStatus::Cancelled);
+    }
+
+    #[tokio::test]
+    async fn a_due_retry_is_claimed_only_once() {
+        let tracker = ProcessedJobsTracker::new(test_pool("guarded_retry").await);
+        tracker.claim(1).await.unwrap();
+        tracker
+            .mark_failed(1, "node unavailable", chrono::Utc::now() - chrono::Duration::seconds(1))
+            .await
+            .unwrap();
+
+        assert_eq!(tracker.fetch_retryable_jobs(10).await.unwrap(), vec![1]);
+        assert_eq!(tracker.claim_retry(1).await.unwrap(), Some(1));
+        assert_eq!(tracker.claim_retry(1).await.unwrap(), None);
+        assert_eq!(status(&tracker, 1).await, JobStatus::Claimed);
+    }
+}
@@ -0,0 +1,131 @@
+use crate::error::AppResult;
+use redis::{Client, Script};
+use std::time::Duration;
+use tracing::{debug, warn};
+
+/// Takes the lock and hands out the next fencing token in one step
+const ACQUIRE_SCRIPT: &str = r#"
+if redis.call('SET', KEYS[1], ARGV[1], 'NX', 'PX', ARGV[2]) then
+    return redis.call('INCR', KEYS[2])
+end
+return false
+"#;
+
+/// Extends the lock only while it is still held by this owner
+const RENEW_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('PEXPIRE', KEYS[1], ARGV[2])
+end
+return 0
+"#;
+
+/// Deletes the lock only while it is still held by this owner
+const RELEASE_SCRIPT: &str = r#"
+if redis.call('GET', KEYS[1]) == ARGV[1] then
+    return redis.call('DEL', KEYS[1])
+end
+return 0
+"#;
+
+/// Leadership over one named role, held as a Redis key with a TTL.
+/// Every successful acquisition gets a strictly increasing fencing token,
+/// so a leader that stalled past its TTL can be told apart from its successor.
+pub struct LeaderElection {
+    client: Client,
+    role: String,
+    lock_key: String,
+    fencing_key: String,
+    owner: String,
+    ttl: Duration,
+}
+
+impl LeaderElection {
+    pub fn new(client: Client, role: &str, owner: String, ttl: Duration) -> Self {
+        Self {
+            client,
+            role: role.to_string(),
+            lock_key: format!("leader:{}", role),
+            fencing_key: format!("leader:{}:fencing", role),
+            owner,
+            ttl,
+        }
+    }
+
+    pub fn role(&self) -> &str {
+        &self.role
+    }
+
+    /// How often the lock should be renewed to survive one missed renewal
+    pub fn renew_interval(&self) -> Duration {
+        self.ttl / 3
+    }
+
+    /// Returns the fencing token if leadership was acquired
+    pub async fn try_acquire(&self) -> AppResult<Option<u64>> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let token: Option<u64> = Script::new(ACQUIRE_SCRIPT)
+            .key(&self.lock_key)
+            .key(&self.fencing_key)
+            .arg(&self.owner)
+            .arg(self.ttl.as_millis() as u64)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(token)
+    }
+
+    /// Returns false once the lock has expired or been taken over
+    pub async fn renew(&self) -> AppResult<bool> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let renewed: i64 = Script::new(RENEW_SCRIPT)
+            .key(&self.lock_key)
+            .arg(&self.owner)
+            .arg(self.ttl.as_millis() as u64)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(renewed == 1)
+    }
+
+    pub async fn release(&self) -> AppResult<()> {
+        let mut conn = self.client.get_multiplexed_async_connection().await?;
+        let _: i64 = Script::new(RELEASE_SCRIPT)
+            .key(&self.lock_key)
+            .arg(&self.owner)
+            .invoke_async(&mut conn)
+            .await?;
+
+        Ok(())
+    }
+
+    /// Keeps renewing and returns as soon as leadership can no longer be confirmed.
+    /// One failed renewal is retried at the next interval, which still falls
+    /// within the TTL; a second one in a row counts as lost, because another
+    /// replica may take over once the TTL runs out.
+    pub async fn hold(&self) {
+        let mut failed_renewals = 0;
+
+        loop {
+            tokio::time::sleep(self.renew_interval()).await;
+
+            match self.renew().await {
+                Ok(true) => {
+                    failed_renewals = 0;
+                    debug!("Renewed leadership of {}", self.lock_key);
+                }
+                Ok(false) => {
+                    warn!("Lost leadership of {}", self.lock_key);
+                    return;
+                }
+                Err(e) if failed_renewals == 0 => {
+                    failed_renewals += 1;
+                    warn!("Could not renew leadership of {}, retrying: {}", self.lock_key, e);
+                }
+                Err(e) => {
+                    warn!("Could not renew leadership of {} again, stepping down: {}", self.lock_key, e);
+                    return;
+                }
+            }
+        }
+    }
+}
@@ -7,7 +7,15 @@ pub mod infrastructure;
 pub mod shared;
 
 pub use api::routes::{start_server, AppState};
+pub use application::worker::anchoring_worker::AnchoringWorker;
+pub use application::worker::confirmation_worker::ConfirmationWorker;
+pub use application::worker::fee_bump_worker::FeeBumpWorker;
+pub use application::worker::leader_elected_worker::LeaderElectedService;
+pub use application::worker::metrics::WorkerMetrics;
+pub use application::worker::nonce_worker::NonceWorker;
 pub use application::worker::polling_worker::PollingWorker;
+pub use application::worker::retry_worker::RetryWorker;
+pub use application::worker::stale_job_worker::StaleJobWorker;
 pub use config::Config;
 pub use domain::models::transaction::{Transaction, TransactionPayload};
 pub use domain::services::transaction_processor::{PostgresTransactionRepository, TransactionProcessorService};
@@ -15,5 +23,7 @@ pub use error::{AppError, AppResult};
 pub use infrastructure::database::connection::{create_pool, run_migrations};
 pub use infrastructure::database::repositories::processed_jobs_repo::ProcessedJobsTracker;
 pub use infrastructure::blockchain::client::BlockchainClient;
+pub use infrastructure::blockchain::nonce_manager::NonceManager;
+pub use infrastructure::blockchain::simulated::SimulatedBlockchainClient;
 pub use infrastructure::redis::client::create_redis_client;
 pub use shared::traits::*;
@@ -1,9 +1,11 @@
 use anyhow::Result;
 use dotenvy::dotenv;
 use rust_polling::{
-    config::Config,
-    create_pool, create_redis_client, run_migrations, BlockchainClient, start_server,
+    config::{BlockchainMode, Config},
+    create_pool, create_redis_client, run_migrations, BlockchainClient, BlockchainService,
+    NonceManager, SimulatedBlockchainClient, start_server,
 };
+use std::{sync::Arc, time::Duration};
 use tracing::info;
 
 #[tokio::main]
@@ -16,10 +18,22 @@ async fn main() -> Result<()> {
 
     let db_pool = create_pool(&config.database.url).await?;
     let redis_client = create_redis_client(&config.redis.url)?;
-    let blockchain_client = BlockchainClient::new(&config.blockchain.rpc_url, &config.blockchain.private_key)?;
 
     run_migrations(&db_pool).await?;
 
+    let blockchain_client: Arc<dyn BlockchainService + Send + Sync> = match config.blockchain.mode {
+        BlockchainMode::Rpc => Arc::new(
+            BlockchainClient::connect(
+                &config.blockchain,
+                NonceManager::new(db_pool.clone()).with_reservation_grace(Duration::from_secs(
+                    config.blockchain.nonce_reservation_grace_seconds,
+                )),
+            )
+            .await?,
+        ),
+        BlockchainMode::Simulated => Arc::new(SimulatedBlockchainClient::new()),
+    };
+
     info!("Starting Polling Service with Axum web server...");
 
     start_server(config, db_pool, redis_client, blockchain_client).await?;
@@ -1 +1,2 @@
+pub mod shutdown;
 pub mod traits; 
\ No newline at end of file
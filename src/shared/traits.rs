@@ -1,33 +1,183 @@
 use async_trait::async_trait;
-use crate::domain::models::transaction::Transaction;
-use alloy::primitives::{Address, U256};
+use crate::domain::models::{
+    anchor::{AnchorBatch, AnchorLeaf},
+    broadcast::{PublishedTransaction, SentTransaction, SignedTransaction},
+    dead_letter::DeadLetter,
+    leadership::FencingToken,
+    processed_job::{ConfirmedJob, JobStatus, ProcessedJob, SentJob, StaleJob},
+    receipt::TransactionReceipt,
+    transaction::{PriorityFee, Transaction, TransactionPriority},
+};
+use alloy::primitives::{Address, Bytes, U256};
 use crate::error::AppResult;
 
 #[async_trait]
 pub trait TransactionRepository {
-    async fn fetch_new_transactions(&self, since: chrono::DateTime<chrono::Utc>) -> AppResult<Vec<Transaction>>;
+    async fn claim_new_transactions(
+        &self,
+        owner: &str,
+        after_id: i64,
+        priority: Option<TransactionPriority>,
+        limit: i64,
+        lease_seconds: i64,
+    ) -> AppResult<Vec<Transaction>>;
+    async fn complete_transaction(&self, id: i32, owner: &str) -> AppResult<()>;
+    async fn release_transaction(&self, id: i32, owner: &str) -> AppResult<()>;
+    async fn find_transaction(&self, id: i32) -> AppResult<Option<Transaction>>;
+    async fn fetch_scheduled_transactions(&self, limit: i64) -> AppResult<Vec<Transaction>>;
+}
+
+#[async_trait]
+pub trait CursorStore {
+    async fn load_cursor(&self, worker_name: &str) -> AppResult<i64>;
+    async fn advance_cursor(&self, worker_name: &str) -> AppResult<i64>;
+}
+
+#[async_trait]
+pub trait TransactionListener {
+    async fn wait_for_notification(&mut self) -> AppResult<()>;
 }
 
 #[async_trait]
 pub trait ProcessedJobsTracker {
+    async fn get_all_processed(&self, status: Option<JobStatus>, limit: i64) -> AppResult<Vec<ProcessedJob>>;
     async fn is_processed(&self, record_id: i64) -> AppResult<bool>;
-    async fn mark_pending(&self, record_id: i64) -> AppResult<()>;
-    async fn mark_sent(&self, record_id: i64, tx_hash: &str) -> AppResult<()>;
-    async fn mark_failed(&self, record_id: i64) -> AppResult<()>;
+    async fn claim(&self, record_id: i64) -> AppResult<bool>;
+    async fn mark_validated(&self, record_id: i64) -> AppResult<bool>;
+    async fn mark_signed(&self, record_id: i64, signed: &SignedTransaction) -> AppResult<bool>;
+    async fn mark_broadcast(&self, record_id: i64, sent: &SentTransaction) -> AppResult<bool>;
+    async fn mark_failed(
+        &self,
+        record_id: i64,
+        error: &str,
+        next_attempt_at: chrono::DateTime<chrono::Utc>,
+    ) -> AppResult<()>;
+    async fn dead_letter(&self, record_id: i64, error: &str) -> AppResult<Option<i64>>;
+    async fn fetch_dead_letters(&self, include_replayed: bool, limit: i64) -> AppResult<Vec<DeadLetter>>;
+    async fn find_dead_letter(&self, id: i64) -> AppResult<Option<DeadLetter>>;
+    async fn replay_dead_letter(&self, id: i64) -> AppResult<bool>;
+    async fn fetch_retryable_jobs(&self, limit: i64) -> AppResult<Vec<i64>>;
+    async fn claim_retry(&self, record_id: i64) -> AppResult<Option<i32>>;
+    async fn fetch_stale_jobs(&self, updated_before: chrono::DateTime<chrono::Utc>, limit: i64) -> AppResult<Vec<StaleJob>>;
+    async fn requeue_stale(&self, job: &StaleJob, reason: &str, fence: Option<&FencingToken>) -> AppResult<bool>;
+    async fn fetch_sent_jobs(&self, limit: i64) -> AppResult<Vec<SentJob>>;
+    async fn fetch_stuck_jobs(&self, sent_before: chrono::DateTime<chrono::Utc>, limit: i64) -> AppResult<Vec<SentJob>>;
+    async fn record_replacement(
+        &self,
+        replaced_tx_hash: &str,
+        signed: &SignedTransaction,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<bool>;
+    async fn get_status(&self, record_id: i64) -> AppResult<Option<(JobStatus, Option<String>)>>;
+    async fn cancel_unbroadcast(&self, record_id: i64) -> AppResult<bool>;
+    async fn cancel_signed(&self, record_id: i64, tx_hash: &str) -> AppResult<Option<i64>>;
+    async fn count_jobs_sharing(&self, tx_hash: &str) -> AppResult<i64>;
+    async fn mark_cancelling(
+        &self,
+        record_id: i64,
+        replaced_tx_hash: &str,
+        signed: &SignedTransaction,
+    ) -> AppResult<()>;
+    async fn mark_cancelled(
+        &self,
+        record_id: i64,
+        receipt: &TransactionReceipt,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<()>;
+    async fn mark_confirmed(
+        &self,
+        record_id: i64,
+        receipt: &TransactionReceipt,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<()>;
+    async fn mark_reverted(
+        &self,
+        record_id: i64,
+        receipt: &TransactionReceipt,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<()>;
+    async fn fetch_confirmed_since(&self, min_block: u64, limit: i64) -> AppResult<Vec<ConfirmedJob>>;
+    async fn mark_reorged(
+        &self,
+        job: &ConfirmedJob,
+        canonical_block_hash: Option<[u8; 32]>,
+        fence: Option<&FencingToken>,
+    ) -> AppResult<()>;
+}
+
+#[async_trait]
+pub trait AnchorStore {
+    async fn save_batch(&self, batch: &AnchorBatch) -> AppResult<i64>;
+    async fn find_proof(&self, record_id: i64) -> AppResult<Option<AnchorLeaf>>;
 }
 
 #[async_trait]
 pub trait BlockchainService {
-    async fn send_transaction(&self, to: Address, value: U256) -> AppResult<[u8; 32]>;
+    fn signer_address(&self) -> Address;
+    async fn sign_transaction(
+        &self,
+        to: Address,
+        value: U256,
+        priority_fee: PriorityFee,
+    ) -> AppResult<SignedTransaction>;
+    async fn sign_token_transfer(
+        &self,
+        token: Address,
+        to: Address,
+        amount: U256,
+        priority_fee: PriorityFee,
+    ) -> AppResult<SignedTransaction>;
+    async fn sign_contract_call(
+        &self,
+        to: Address,
+        value: U256,
+        data: Bytes,
+        priority_fee: PriorityFee,
+    ) -> AppResult<SignedTransaction>;
+    async fn broadcast_transaction(&self, signed: &SignedTransaction) -> AppResult<SentTransaction>;
+    async fn token_decimals(&self, token: Address) -> AppResult<u8>;
+    async fn sign_replacement(
+        &self,
+        tx_hash: [u8; 32],
+        bump_percent: u64,
+        max_fee_per_gas_cap: u128,
+    ) -> AppResult<Option<SignedTransaction>>;
+    async fn sign_cancellation(
+        &self,
+        tx_hash: [u8; 32],
+        bump_percent: u64,
+        max_fee_per_gas_cap: u128,
+    ) -> AppResult<Option<SignedTransaction>>;
+    async fn get_transaction(&self, tx_hash: [u8; 32]) -> AppResult<Option<PublishedTransaction>>;
+    async fn get_transaction_receipt(&self, tx_hash: [u8; 32]) -> AppResult<Option<TransactionReceipt>>;
+    async fn get_block_number(&self) -> AppResult<u64>;
+    async fn get_block_hash(&self, block_number: u64) -> AppResult<Option<[u8; 32]>>;
+    async fn mined_nonce(&self) -> AppResult<u64>;
+    async fn release_nonce(&self, nonce: u64) -> AppResult<()>;
+    async fn resync_nonces(&self, fence: Option<&FencingToken>) -> AppResult<()>;
+    async fn fill_nonce_gaps(&self, fence: Option<&FencingToken>) -> AppResult<usize>;
 }
 
 #[async_trait]
 pub trait TransactionProcessor {
     async fn process_transaction(&self, transaction: &Transaction) -> AppResult<()>;
+    async fn retry_transaction(&self, transaction: &Transaction) -> AppResult<()>;
 }
 
 #[async_trait]
 pub trait AppService {
     async fn start(&mut self) -> AppResult<()>;
     async fn stop(&self) -> AppResult<()>;
+}
+
+/// A service that runs as the leader of a role and fences its writes with
+/// the token it was elected with
+pub trait Fenced {
+    fn set_fencing_token(&mut self, token: FencingToken);
+}
+
+#[async_trait]
+pub trait LeaderTokenStore {
+    /// Records a new leader of `role` and returns the token its writes must carry
+    async fn register(&self, role: &str, election_token: u64) -> AppResult<FencingToken>;
 } 
\ No newline at end of file